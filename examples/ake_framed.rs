@@ -0,0 +1,86 @@
+//! Example demonstrating an authenticated key exchange (AKE) over a framed byte transport.
+//!
+//! Alice and Bob run the same exchange as the `ake` example, but each handshake message is
+//! tagged and length-prefixed with `kyberlib::frame` and written to a shared byte buffer
+//! standing in for a socket, so the receiver can tell an initiation from a response and
+//! knows how many bytes to read.
+//!
+//! # Usage
+//!
+//! 1. Ensure you have the `kyberlib` crate installed and added as a dependency in your `Cargo.toml`.
+//! 2. Build and run this example with `cargo run --example ake_framed`.
+//!
+//! # Note
+//!
+//! The keys are generated up front and shared in memory. A real deployment would distribute
+//! the long-term public keys out of band.
+//!
+//! # License
+//!
+//! This example is licensed under either of the following, at your choice:
+//!
+//! - Apache License, Version 2.0 (LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0)
+//! - MIT License (LICENSE-MIT or http://opensource.org/licenses/MIT)
+
+use kyberlib::frame::*;
+use kyberlib::*;
+
+// Takes the next frame off the wire, checking its kind and length
+fn read_frame<const N: usize>(
+    wire: &mut Vec<u8>,
+    expected: u8,
+) -> Result<[u8; N], KyberLibError> {
+    let (kind, payload, rest) = parse_frame(wire)?;
+    if kind != expected {
+        return Err(KyberLibError::InvalidInput);
+    }
+    if payload.len() != N {
+        return Err(KyberLibError::InvalidLength);
+    }
+    let mut msg = [0u8; N];
+    msg.copy_from_slice(payload);
+    *wire = rest.to_vec();
+    Ok(msg)
+}
+
+fn main() -> Result<(), KyberLibError> {
+    let mut rng = rand::thread_rng();
+    let mut wire = Vec::new();
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let alice_keys = keypair(&mut rng)?;
+    let bob_keys = keypair(&mut rng)?;
+
+    // Alice frames her initiation and writes it to the wire
+    let client_send = alice.client_init(&bob_keys.public, &mut rng)?;
+    wire.extend_from_slice(&frame_message(
+        FRAME_AKE_INIT,
+        &client_send,
+    ));
+
+    // Bob reads the initiation frame and writes back his response
+    let client_recv: AkeSendInit =
+        read_frame(&mut wire, FRAME_AKE_INIT)?;
+    let server_send = bob.server_receive(
+        client_recv,
+        &alice_keys.public,
+        &bob_keys.secret,
+        &mut rng,
+    )?;
+    wire.extend_from_slice(&frame_message(
+        FRAME_AKE_RESPONSE,
+        &server_send,
+    ));
+
+    // Alice reads the response frame and decapsulates
+    let server_recv: AkeSendResponse =
+        read_frame(&mut wire, FRAME_AKE_RESPONSE)?;
+    alice.client_confirm(server_recv, &alice_keys.secret)?;
+
+    // Both structs now have the shared secret
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+    assert!(wire.is_empty());
+
+    Ok(())
+}
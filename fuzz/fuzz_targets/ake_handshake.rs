@@ -0,0 +1,51 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Feeds arbitrary bytes to both sides of a mutual key exchange.
+
+#![no_main]
+
+use kyberlib::*;
+use libfuzzer_sys::fuzz_target;
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::OnceLock;
+
+// Client and server keys shared by every run
+fn static_keys() -> &'static (Keypair, Keypair) {
+    static KEYS: OnceLock<(Keypair, Keypair)> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let mut rng = StdRng::seed_from_u64(0);
+        let client = keypair(&mut rng).expect("keypair");
+        let server = keypair(&mut rng).expect("keypair");
+        (client, server)
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (client_keys, server_keys) = static_keys();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    // Server side: the initiation comes straight off the wire
+    let mut bob = Ake::new();
+    if bob
+        .server_receive_slice(
+            data,
+            &client_keys.public,
+            &server_keys.secret,
+            &mut rng,
+        )
+        .is_ok()
+    {
+        assert_eq!(data.len(), AKE_INIT_BYTES);
+    }
+
+    // Client side: an honest initiation answered with arbitrary bytes
+    let mut send_b = [0u8; AKE_RESPONSE_BYTES];
+    let len = data.len().min(AKE_RESPONSE_BYTES);
+    send_b[..len].copy_from_slice(&data[..len]);
+    let mut alice = Ake::new();
+    let _ = alice
+        .client_init(&server_keys.public, &mut rng)
+        .expect("init");
+    let _ = alice.client_confirm(send_b, &client_keys.secret);
+});
@@ -0,0 +1,42 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Feeds arbitrary bytes to both sides of a unilateral key exchange.
+
+#![no_main]
+
+use kyberlib::*;
+use libfuzzer_sys::fuzz_target;
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::OnceLock;
+
+// Server keys shared by every run, so inputs only drive the messages
+fn server_keys() -> &'static Keypair {
+    static KEYS: OnceLock<Keypair> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        keypair(&mut StdRng::seed_from_u64(0)).expect("keypair")
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let keys = server_keys();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    // Server side: the initiation comes straight off the wire
+    let mut bob = Uake::new();
+    if bob
+        .server_receive_slice(data, &keys.secret, &mut rng)
+        .is_ok()
+    {
+        assert_eq!(data.len(), UAKE_INIT_BYTES);
+    }
+
+    // Client side: an honest initiation answered with arbitrary bytes,
+    // which implicit rejection turns into an unrelated secret
+    let mut send_b = [0u8; UAKE_RESPONSE_BYTES];
+    let len = data.len().min(UAKE_RESPONSE_BYTES);
+    send_b[..len].copy_from_slice(&data[..len]);
+    let mut alice = Uake::new();
+    let _ = alice.client_init(&keys.public, &mut rng).expect("init");
+    let _ = alice.client_confirm(send_b);
+});
@@ -1,16 +1,35 @@
 // Copyright © 2024 kyberlib. All rights reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+extern crate alloc;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{Operation, Timer};
+#[cfg(debug_assertions)]
+use crate::symmetric::hash_h_unchecked;
 use crate::{
     error::KyberLibError,
+    fips202::{
+        keccak_absorb_once, keccak_squeeze, sha3_256, shake256,
+        shake256_concat, SHAKE256_RATE,
+    },
+    indcpa::{indcpa_expand_at, PublicMatrix},
     kem::*,
-    kex::{Decapsulated, Encapsulated, PublicKey, SecretKey},
+    kex::{
+        Decapsulated, Encapsulated, PublicKey, SecretKey, SharedSecret,
+    },
     params::*,
+    polyvec::Polyvec,
+    symmetric::KeccakState,
     CryptoRng, RngCore,
 };
+use alloc::{string::String, vec::Vec};
+use core::{convert::TryFrom, fmt::Write};
 use pqc_core::zero;
+use subtle::ConstantTimeEq;
+use zeroize::DefaultIsZeroes;
 #[cfg(feature = "zeroize")]
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 /// Generate a key pair for Kyber encryption with a provided RNG.
 ///
@@ -36,6 +55,8 @@ pub fn keypair<R>(rng: &mut R) -> Result<Keypair, KyberLibError>
 where
     R: RngCore + CryptoRng,
 {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Keypair);
     let mut public = [0u8; KYBER_PUBLIC_KEY_BYTES];
     let mut secret = [0u8; KYBER_SECRET_KEY_BYTES];
     generate_key_pair(&mut public, &mut secret, rng, None)?;
@@ -46,6 +67,14 @@ where
 
 /// Verify that given secret and public key matches and put them in
 /// the KeyPair structure after zeroize them if asked.
+///
+/// The check encapsulates to `public` and decapsulates with `secret`.
+/// The two shared secrets are compared in constant time, and the
+/// keypair is assembled on both the matching and the mismatching
+/// path, so the time taken does not reveal how close crafted keys came
+/// to matching. The inputs are only zeroized when the keys match, so
+/// the caller keeps them after an `InvalidKey` error.
+///
 /// ### Example
 /// ```
 /// # use kyberlib::*;
@@ -66,26 +95,26 @@ where
     R: RngCore + CryptoRng,
 {
     //Try to encapsulate and decapsulate to verify secret key matches public key
-    let (ciphertext, shared_secret) = encapsulate(public, rng)?;
-    let expected_shared_secret = decapsulate(&ciphertext, secret)?;
-    //If it does match, return a KeyPair
-    if expected_shared_secret == shared_secret {
-        let public2 = *public;
-        let secret2 = *secret;
-        let key = Keypair {
-            public: public2,
-            secret: secret2,
-        };
+    let (ciphertext, mut shared_secret) = encapsulate(public, rng)?;
+    let mut expected_shared_secret = decapsulate(&ciphertext, secret)?;
+    let matches: bool =
+        expected_shared_secret.ct_eq(&shared_secret).into();
+    wipe(&mut shared_secret);
+    wipe(&mut expected_shared_secret);
+    // Build the keypair on both paths
+    let mut key = Keypair {
+        public: *public,
+        secret: *secret,
+    };
+    if matches {
         #[cfg(feature = "zeroize")]
         {
             public.zeroize();
             secret.zeroize();
-            public2.zeroize();
-            secret2.zeroize();
         }
         Ok(key)
     } else {
-        //Else return an error
+        wipe(&mut key.secret);
         Err(KyberLibError::InvalidKey)
     }
 }
@@ -116,6 +145,8 @@ pub fn encapsulate<R>(pk: &[u8], rng: &mut R) -> Encapsulated
 where
     R: CryptoRng + RngCore,
 {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
     if pk.len() != KYBER_PUBLIC_KEY_BYTES {
         return Err(KyberLibError::InvalidInput);
     }
@@ -125,18 +156,70 @@ where
     Ok((ct, ss))
 }
 
-/// Decapsulates ciphertext with a secret key.
+/// Encapsulates a public key and also returns the ciphertext hash.
 ///
-/// This function decapsulates ciphertext with a secret key and returns the shared secret.
+/// `ct_hash` is H(ciphertext), which encapsulation computes anyway to
+/// derive the shared secret: SHA3-256, or SHA-256 in 90s mode. It is
+/// public and can key a replay or deduplication cache without hashing
+/// the ciphertext a second time.
 ///
-/// # Arguments
+/// # Errors
 ///
-/// * `ct` - The ciphertext as a slice of bytes.
-/// * `sk` - The secret key as a slice of bytes.
+/// Returns `KyberLibError::InvalidInput` if the public key is
+/// incorrectly sized, or the error from the RNG.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss, ct_hash) = encapsulate_with_ct_hash(&keys.public, &mut rng)?;
+/// assert_eq!(decapsulate(&ct, &keys.secret)?, ss);
+/// assert_eq!(ct_hash.len(), 32);
+/// # Ok(())}
+/// ```
+pub fn encapsulate_with_ct_hash<R>(
+    pk: &[u8],
+    rng: &mut R,
+) -> Result<
+    (
+        [u8; KYBER_CIPHERTEXT_BYTES],
+        SharedSecret,
+        [u8; KYBER_SYM_BYTES],
+    ),
+    KyberLibError,
+>
+where
+    R: CryptoRng + RngCore,
+{
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    let ct_hash = encrypt_message_with_ct_hash(
+        &mut ct, &mut ss, pk, rng, None, None,
+    )?;
+    Ok((ct, ss, ct_hash))
+}
+
+// Size of the recipient count opening a multi-recipient container
+const RECIPIENT_COUNT_BYTES: usize = 4;
+
+/// Encapsulates a public key, returning the shared secret in a wrapper
+/// that wipes it on drop.
+///
+/// The same as [`encapsulate`], except that the shared secret is held
+/// in a `Zeroizing` buffer so it is cleared as soon as it goes out of
+/// scope. The ciphertext is public and stays a plain array.
 ///
 /// # Errors
 ///
-/// Returns a `KyberLibError` if the input sizes are incorrect or if decapsulation fails.
+/// Returns `KyberLibError::InvalidInput` if the public key is
+/// incorrectly sized, or the error from the RNG.
 ///
 /// ### Example
 /// ```
@@ -144,160 +227,2143 @@ where
 /// # fn main() -> Result<(), KyberLibError> {
 /// let mut rng = rand::thread_rng();
 /// let keys = keypair(&mut rng)?;
-/// let (ct, ss1) = encapsulate(&keys.public, &mut rng)?;
-/// let ss2 = decapsulate(&ct, keys.expose_secret())?;
-/// assert_eq!(ss1, ss2);
-/// #  Ok(())}
+/// let (ct, ss) = encapsulate_zeroizing(&keys.public, &mut rng)?;
+/// assert_eq!(decapsulate(&ct, &keys.secret)?, *ss);
+/// # Ok(())}
 /// ```
-pub fn decapsulate(ct: &[u8], sk: &[u8]) -> Decapsulated {
-    if ct.len() != KYBER_CIPHERTEXT_BYTES
-        || sk.len() != KYBER_SECRET_KEY_BYTES
-    {
+#[cfg(feature = "zeroize")]
+pub fn encapsulate_zeroizing<R>(
+    pk: &[u8],
+    rng: &mut R,
+) -> Result<
+    (
+        [u8; KYBER_CIPHERTEXT_BYTES],
+        Zeroizing<[u8; KYBER_SHARED_SECRET_BYTES]>,
+    ),
+    KyberLibError,
+>
+where
+    R: CryptoRng + RngCore,
+{
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES {
         return Err(KyberLibError::InvalidInput);
     }
-    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
-    decrypt_message(&mut ss, ct, sk);
-    Ok(ss)
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut ss = Zeroizing::new([0u8; KYBER_SHARED_SECRET_BYTES]);
+    encrypt_message(&mut ct, &mut ss[..], pk, rng, None)?;
+    Ok((ct, ss))
 }
 
-/// A public/secret keypair for use with Kyber.
+/// Encapsulates a public key, returning the shared secret behind a
+/// move-only handle.
 ///
-/// Byte lengths of the keys are determined by the security level chosen.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-pub struct Keypair {
-    /// The public key.
-    pub public: PublicKey,
-    /// The secret key.
-    pub secret: SecretKey,
+/// For delegation, where a proxy hands the ciphertext to the recipient
+/// and the shared secret to a separate party. The handle is neither
+/// `Clone` nor `Copy`, and [`SharedSecretHandle::extract`] consumes it,
+/// so the secret cannot be duplicated by accident.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if the public key is
+/// incorrectly sized, or the error from the RNG.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, handle) = encapsulate_split(&keys.public, &mut rng)?;
+/// assert_eq!(decapsulate(&ct, &keys.secret)?, handle.extract());
+/// # Ok(())}
+/// ```
+///
+/// The handle cannot be extracted twice:
+/// ```compile_fail
+/// # use kyberlib::*;
+/// # let mut rng = rand::thread_rng();
+/// # let keys = keypair(&mut rng).unwrap();
+/// let (_, handle) = encapsulate_split(&keys.public, &mut rng).unwrap();
+/// let first = handle.extract();
+/// let second = handle.extract();
+/// ```
+pub fn encapsulate_split<R>(
+    pk: &[u8],
+    rng: &mut R,
+) -> Result<
+    ([u8; KYBER_CIPHERTEXT_BYTES], SharedSecretHandle),
+    KyberLibError,
+>
+where
+    R: CryptoRng + RngCore,
+{
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut handle = SharedSecretHandle {
+        secret: [0u8; KYBER_SHARED_SECRET_BYTES],
+    };
+    encrypt_message(&mut ct, &mut handle.secret, pk, rng, None)?;
+    Ok((ct, handle))
 }
 
-impl Keypair {
-    /// Securely generates a new keypair.
-    ///
-    /// This function generates a new Kyber key pair and returns it as a `Keypair` struct.
-    ///
-    /// # Arguments
-    ///
-    /// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
-    ///
-    /// ### Example
-    /// ```
-    /// # use kyberlib::*;
-    /// # fn main() -> Result<(), KyberLibError> {
-    /// let mut rng = rand::thread_rng();
-    /// let keys = Keypair::generate(&mut rng)?;
-    /// # let empty_keys = Keypair{
-    ///   public: [0u8; KYBER_PUBLIC_KEY_BYTES], secret: [0u8; KYBER_SECRET_KEY_BYTES]
-    /// };
-    /// # assert!(empty_keys != keys);
-    /// # Ok(()) }
-    /// ```
-    pub fn generate<R: CryptoRng + RngCore>(
-        rng: &mut R,
-    ) -> Result<Keypair, KyberLibError> {
-        keypair(rng)
+/// A shared secret that can be extracted exactly once.
+///
+/// Returned by [`encapsulate_split`]. The secret is wiped when the
+/// handle is dropped, including after [`extract`](Self::extract), and
+/// is not shown by `Debug`.
+pub struct SharedSecretHandle {
+    secret: SharedSecret,
+}
+
+impl SharedSecretHandle {
+    /// Consumes the handle and returns the shared secret.
+    pub fn extract(self) -> SharedSecret {
+        self.secret
     }
+}
 
-    /// Explicitly exposes the secret key
-    ///```
-    /// use kyberlib::*;
-    ///
-    /// let mut rng = rand::thread_rng();
-    /// let keys = Keypair::generate(&mut rng);
-    /// let binding = keys.expect("Exposed secret key");
-    /// let secret = binding.expose_secret();
-    /// assert!(secret.len() == KYBER_SECRET_KEY_BYTES);
-    /// assert!(secret.len() != 0);
-    /// ```
-    pub fn expose_secret(&self) -> &SecretKey {
-        &self.secret
+impl Drop for SharedSecretHandle {
+    fn drop(&mut self) {
+        wipe(&mut self.secret);
+    }
+}
+
+// Keeps the shared secret out of logs and panic messages
+impl core::fmt::Debug for SharedSecretHandle {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        f.write_str("SharedSecretHandle(..)")
     }
+}
 
-    /// Imports a keypair from existing public and secret key arrays.
-    ///
-    /// This function imports a keypair from existing public and secret key arrays and returns it as a `Keypair` struct.
+/// Deterministically encapsulates to `pk` with coins derived from a
+/// seed and a counter.
+///
+/// The encapsulation coins are SHAKE256 of `base_seed || counter`, with
+/// the counter as 8 little-endian bytes. Each counter value yields
+/// distinct coins even when the seed is fixed, so a caller can number
+/// its messages instead of trusting an RNG per call, and the same
+/// `(pk, base_seed, counter)` always reproduces the same ciphertext and
+/// shared secret.
+///
+/// `base_seed` must be secret and uniformly random: anyone who knows it
+/// can recompute every shared secret. Never reuse a counter with the
+/// same seed and public key unless the repeat is intended.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if the public key is
+/// incorrectly sized.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = keypair(&mut rand::thread_rng())?;
+/// let seed = [7u8; 32];
+/// let (ct, ss) = encapsulate_counter(&keys.public, &seed, 0)?;
+/// assert_eq!(decapsulate(&ct, &keys.secret)?, ss);
+/// assert_ne!(encapsulate_counter(&keys.public, &seed, 1)?.0, ct);
+/// # Ok(())}
+/// ```
+pub fn encapsulate_counter(
+    pk: &[u8],
+    base_seed: &[u8; 32],
+    counter: u64,
+) -> Encapsulated {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let mut coins = [0u8; KYBER_SYM_BYTES];
+    shake256_concat(
+        &mut coins,
+        KYBER_SYM_BYTES,
+        &[base_seed, &counter.to_le_bytes()],
+    );
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    let result = encrypt_message(
+        &mut ct,
+        &mut ss,
+        pk,
+        &mut DummyRng {},
+        Some(&coins),
+    );
+    wipe(&mut coins);
+    result.map(|()| (ct, ss))
+}
+
+/// Ciphertexts for several recipients in one serialized container.
+///
+/// The layout is the recipient count `n` as a 4-byte big-endian integer
+/// followed by the `n` ciphertexts back to back, in the order of the
+/// public keys given to [`encapsulate_to_all`]. The sender keeps the
+/// matching shared secrets; each recipient picks out its ciphertext
+/// with [`extract_for`] and decapsulates it as usual.
+///
+/// [`encapsulate_to_all`]: MultiRecipientCiphertext::encapsulate_to_all
+/// [`extract_for`]: MultiRecipientCiphertext::extract_for
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultiRecipientCiphertext {
+    bytes: Vec<u8>,
+}
+
+impl MultiRecipientCiphertext {
+    /// Encapsulates a fresh shared secret to each public key.
     ///
-    /// # Arguments
+    /// Returns the container and the shared secrets, in the order of
+    /// `pks`.
     ///
-    /// * `public` - A mutable reference to a `[u8; KYBER_PUBLIC_KEY_BYTES]` array representing the public key.
-    /// * `secret` - A mutable reference to a `[u8; KYBER_SECRET_KEY_BYTES]` array representing the secret key.
-    /// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
+    /// # Errors
     ///
-    /// # Example
+    /// Returns `KyberLibError::InvalidInput` if there are more than
+    /// `u32::MAX` recipients, or the error from the RNG.
     ///
+    /// ### Example
     /// ```
     /// # use kyberlib::*;
     /// # fn main() -> Result<(), KyberLibError> {
     /// let mut rng = rand::thread_rng();
-    /// let keys = keypair(&mut rng)?;
-    /// let mut public_key = keys.public;
-    /// let mut secret_key = keys.secret;
-    /// let keys = Keypair::import(&mut public_key, &mut secret_key, &mut rng)?;
-    /// let _ = Keypair::import(&mut public_key, &mut secret_key, &mut rng)?;
-    /// # Ok(()) }
+    /// let alice = keypair(&mut rng)?;
+    /// let bob = keypair(&mut rng)?;
+    /// let (container, secrets) = MultiRecipientCiphertext::encapsulate_to_all(
+    ///     &[alice.public, bob.public],
+    ///     &mut rng,
+    /// )?;
+    /// let ct = MultiRecipientCiphertext::extract_for(container.as_bytes(), 1)?;
+    /// assert_eq!(decapsulate(&ct, &bob.secret)?, secrets[1]);
+    /// # Ok(())}
     /// ```
-    pub fn import<R: CryptoRng + RngCore>(
-        public: &mut [u8; KYBER_PUBLIC_KEY_BYTES],
-        secret: &mut [u8; KYBER_SECRET_KEY_BYTES],
+    pub fn encapsulate_to_all<R>(
+        pks: &[PublicKey],
         rng: &mut R,
-    ) -> Result<Keypair, KyberLibError> {
-        keypairfrom(public, secret, rng)
+    ) -> Result<(Self, Vec<SharedSecret>), KyberLibError>
+    where
+        R: CryptoRng + RngCore,
+    {
+        let n = u32::try_from(pks.len())
+            .map_err(|_| KyberLibError::InvalidInput)?;
+        let mut bytes = Vec::with_capacity(
+            RECIPIENT_COUNT_BYTES + pks.len() * KYBER_CIPHERTEXT_BYTES,
+        );
+        bytes.extend_from_slice(&n.to_be_bytes());
+        let mut secrets = Vec::with_capacity(pks.len());
+        for pk in pks {
+            let (ct, ss) = encapsulate(pk, rng)?;
+            bytes.extend_from_slice(&ct);
+            secrets.push(ss);
+        }
+        Ok((Self { bytes }, secrets))
     }
-}
-
-struct DummyRng {}
 
-impl CryptoRng for DummyRng {}
+    /// Returns the serialized container.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
 
-impl RngCore for DummyRng {
-    fn next_u32(&mut self) -> u32 {
-        panic!()
+    /// Returns the number of recipients.
+    pub fn len(&self) -> usize {
+        (self.bytes.len() - RECIPIENT_COUNT_BYTES)
+            / KYBER_CIPHERTEXT_BYTES
     }
 
-    fn next_u64(&mut self) -> u64 {
-        panic!()
+    /// Returns `true` if the container has no recipients.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    fn try_fill_bytes(
-        &mut self,
-        _dest: &mut [u8],
-    ) -> Result<(), rand_core::Error> {
-        panic!()
+    /// Returns the ciphertext for recipient `index` from a serialized
+    /// container.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidLength` if the container is
+    /// truncated or its length disagrees with the recipient count, and
+    /// `KyberLibError::InvalidInput` if `index` is out of range.
+    pub fn extract_for(
+        container: &[u8],
+        index: usize,
+    ) -> Result<[u8; KYBER_CIPHERTEXT_BYTES], KyberLibError> {
+        if container.len() < RECIPIENT_COUNT_BYTES {
+            return Err(KyberLibError::InvalidLength);
+        }
+        let (count, cts) = container.split_at(RECIPIENT_COUNT_BYTES);
+        let mut n = [0u8; RECIPIENT_COUNT_BYTES];
+        n.copy_from_slice(count);
+        let n = u32::from_be_bytes(n) as usize;
+        if cts.len() / KYBER_CIPHERTEXT_BYTES != n
+            || cts.len() % KYBER_CIPHERTEXT_BYTES != 0
+        {
+            return Err(KyberLibError::InvalidLength);
+        }
+        if index >= n {
+            return Err(KyberLibError::InvalidInput);
+        }
+        let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+        ct.copy_from_slice(
+            &cts[index * KYBER_CIPHERTEXT_BYTES..]
+                [..KYBER_CIPHERTEXT_BYTES],
+        );
+        Ok(ct)
     }
+}
 
-    fn fill_bytes(&mut self, _dest: &mut [u8]) {
-        panic!()
+/// Encapsulates a public key, writing the ciphertext and shared secret
+/// directly into caller-provided buffers.
+///
+/// This is the same operation as [`encapsulate`] without copying the
+/// results out through the return value, for use on constrained devices.
+///
+/// # Arguments
+///
+/// * `pk` - The public key as a slice of `KYBER_PUBLIC_KEY_BYTES` bytes.
+/// * `ct_out` - Receives the ciphertext, must be `KYBER_CIPHERTEXT_BYTES` long.
+/// * `ss_out` - Receives the shared secret, must be `KYBER_SHARED_SECRET_BYTES` long.
+/// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if any buffer has an incorrect length,
+/// or a `KyberLibError` if an error occurs during encapsulation.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+/// let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+/// encapsulate_into(&keys.public, &mut ct, &mut ss, &mut rng)?;
+/// assert_eq!(decapsulate(&ct, &keys.secret)?, ss);
+/// # Ok(())}
+/// ```
+pub fn encapsulate_into<R>(
+    pk: &[u8],
+    ct_out: &mut [u8],
+    ss_out: &mut [u8],
+    rng: &mut R,
+) -> Result<(), KyberLibError>
+where
+    R: CryptoRng + RngCore,
+{
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES
+        || ct_out.len() != KYBER_CIPHERTEXT_BYTES
+        || ss_out.len() != KYBER_SHARED_SECRET_BYTES
+    {
+        return Err(KyberLibError::InvalidInput);
     }
+    encrypt_message(ct_out, ss_out, pk, rng, None)
 }
 
-/// Deterministically derive a keypair from a seed as specified in draft-schwabe-cfrg-kyber.
+/// Decapsulates ciphertext with a secret key.
 ///
-/// This function deterministically derives a key pair from a seed and returns it as a `Keypair` struct.
+/// This function decapsulates ciphertext with a secret key and returns the shared secret.
 ///
 /// # Arguments
 ///
-/// * `seed` - The seed as a slice of bytes.
+/// * `ct` - The ciphertext as a slice of bytes.
+/// * `sk` - The secret key as a slice of bytes.
 ///
 /// # Errors
 ///
-/// Returns a `KyberLibError` if the seed length is incorrect.
-pub fn derive(seed: &[u8]) -> Result<Keypair, KyberLibError> {
-    let mut public = [0u8; KYBER_PUBLIC_KEY_BYTES];
-    let mut secret = [0u8; KYBER_SECRET_KEY_BYTES];
-    let mut _rng = DummyRng {};
-    if seed.len() != 64 {
+/// Returns a `KyberLibError` if the input sizes are incorrect or if decapsulation fails.
+///
+/// # Constant time
+///
+/// Once the input lengths have been checked, the time taken does not
+/// depend on whether `ct` is a valid encapsulation. The ciphertext is
+/// always decrypted and re-encrypted, the comparison with `verify`
+/// reduces every byte to a mask, and `cmov` selects between the real
+/// pre-key and the implicit-rejection value `z` without branching. An
+/// invalid ciphertext therefore yields `Ok` with an unrelated secret
+/// rather than an early error. `tests/ct_decapsulate.rs`, behind the
+/// `ct-tests` feature, checks this statistically.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss1) = encapsulate(&keys.public, &mut rng)?;
+/// let ss2 = decapsulate(&ct, keys.expose_secret())?;
+/// assert_eq!(ss1, ss2);
+/// #  Ok(())}
+/// ```
+pub fn decapsulate(ct: &[u8], sk: &[u8]) -> Decapsulated {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Decapsulate);
+    if ct.len() != KYBER_CIPHERTEXT_BYTES
+        || sk.len() != KYBER_SECRET_KEY_BYTES
+    {
         return Err(KyberLibError::InvalidInput);
     }
-    generate_key_pair(
-        &mut public,
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    decrypt_message(&mut ss, ct, sk);
+    Ok(ss)
+}
+
+/// Decapsulates ciphertext, rejecting an all-zero secret key.
+///
+/// An all-zero secret key is almost always uninitialized memory: a real
+/// key embeds H(pk) and the random value `z`, which are never all
+/// zeros in practice. [`decapsulate`] would still return a
+/// deterministic but meaningless secret for it; this variant reports
+/// the bug instead. The check reads every byte of the key, so its
+/// timing does not depend on where a nonzero byte is.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if the input sizes are
+/// incorrect, or `KyberLibError::InvalidKey` if `sk` is all zeros.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss) = encapsulate(&keys.public, &mut rng)?;
+/// assert_eq!(decapsulate_strict(&ct, &keys.secret)?, ss);
+/// assert_eq!(
+///     decapsulate_strict(&ct, &[0u8; KYBER_SECRET_KEY_BYTES]),
+///     Err(KyberLibError::InvalidKey)
+/// );
+/// # Ok(())}
+/// ```
+pub fn decapsulate_strict(ct: &[u8], sk: &[u8]) -> Decapsulated {
+    if ct.len() != KYBER_CIPHERTEXT_BYTES
+        || sk.len() != KYBER_SECRET_KEY_BYTES
+    {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let acc = sk.iter().fold(0u8, |acc, &b| acc | b);
+    if bool::from(acc.ct_eq(&0)) {
+        return Err(KyberLibError::InvalidKey);
+    }
+    decapsulate(ct, sk)
+}
+
+/// Decapsulates a ciphertext **without** the re-encryption check.
+///
+/// # Security
+///
+/// This downgrades Kyber from IND-CCA2 to IND-CPA security. Skipping
+/// the re-encryption of the decrypted message saves roughly the cost of
+/// an encapsulation, but a malformed ciphertext is no longer rejected:
+/// it yields a secret that depends on the decrypted message, and an
+/// attacker who can submit chosen ciphertexts and observe whether the
+/// resulting secrets work can recover the secret key. Only use this
+/// where every ciphertext is already authenticated, e.g. inside a
+/// mutually authenticated TLS tunnel between internal services, and
+/// never with a key that is also used with [`decapsulate`] on
+/// untrusted input.
+///
+/// For honestly generated ciphertexts the shared secret equals that of
+/// [`decapsulate`].
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if `ct` or `sk` are incorrectly
+/// sized.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss) = encapsulate(&keys.public, &mut rng)?;
+/// assert_eq!(decapsulate_no_reencrypt(&ct, &keys.secret)?, ss);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "fast-decap")]
+pub fn decapsulate_no_reencrypt(ct: &[u8], sk: &[u8]) -> Decapsulated {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Decapsulate);
+    if ct.len() != KYBER_CIPHERTEXT_BYTES
+        || sk.len() != KYBER_SECRET_KEY_BYTES
+    {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    decrypt_message_no_reencrypt(&mut ss, ct, sk);
+    Ok(ss)
+}
+
+/// Decapsulates a ciphertext using a public key already extracted from
+/// the secret key.
+///
+/// Produces the same shared secret as [`decapsulate`], but skips copying
+/// the public key out of `sk` on every call, which adds up on a server
+/// decapsulating many ciphertexts under one key.
+///
+/// `pk` must be the public key embedded in `sk`, e.g. from [`public`].
+/// Debug builds assert that its hash matches the one stored in `sk`;
+/// release builds do not check, and a mismatched key makes every
+/// ciphertext fail re-encryption and yield an unrelated secret.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if `ct` or `sk` are incorrectly
+/// sized.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let pk = public(&keys.secret);
+/// let (ct, ss) = encapsulate(&keys.public, &mut rng)?;
+/// assert_eq!(decapsulate_with_pk(&ct, &keys.secret, &pk)?, ss);
+/// # Ok(())}
+/// ```
+pub fn decapsulate_with_pk(
+    ct: &[u8],
+    sk: &[u8],
+    pk: &PublicKey,
+) -> Decapsulated {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Decapsulate);
+    if ct.len() != KYBER_CIPHERTEXT_BYTES
+        || sk.len() != KYBER_SECRET_KEY_BYTES
+    {
+        return Err(KyberLibError::InvalidInput);
+    }
+    #[cfg(debug_assertions)]
+    {
+        const START: usize =
+            KYBER_SECRET_KEY_BYTES - 2 * KYBER_SYM_BYTES;
+        let mut hash = [0u8; KYBER_SYM_BYTES];
+        hash_h_unchecked(&mut hash, pk, KYBER_PUBLIC_KEY_BYTES);
+        debug_assert_eq!(
+            hash[..],
+            sk[START..START + KYBER_SYM_BYTES],
+            "public key does not match the secret key"
+        );
+    }
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    let _ = decrypt_message_with_pk(&mut ss, ct, sk, pk, None);
+    Ok(ss)
+}
+
+/// Encapsulates a public key with the coins bound to an application context.
+///
+/// The `context` (e.g. a session id) is hashed and mixed into the
+/// derivation of the encryption coins and pre-key, so the ciphertext and
+/// shared secret are tied to it. The receiver must call
+/// [`decapsulate_with_context`] with the same context; any other context,
+/// or plain [`decapsulate`], yields an unrelated shared secret.
+///
+/// # Arguments
+///
+/// * `pk` - The public key as a slice of bytes.
+/// * `context` - The application context, of any length.
+/// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if the input sizes are incorrect or if an error occurs during encapsulation.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss1) = encapsulate_with_context(&keys.public, b"session-42", &mut rng)?;
+/// let ss2 = decapsulate_with_context(&ct, &keys.secret, b"session-42")?;
+/// assert_eq!(ss1, ss2);
+/// # Ok(())}
+/// ```
+pub fn encapsulate_with_context<R>(
+    pk: &[u8],
+    context: &[u8],
+    rng: &mut R,
+) -> Encapsulated
+where
+    R: CryptoRng + RngCore,
+{
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    encrypt_message_with_context(
+        &mut ct,
+        &mut ss,
+        pk,
+        rng,
+        None,
+        Some(context),
+    )?;
+    Ok((ct, ss))
+}
+
+/// Decapsulates ciphertext produced by [`encapsulate_with_context`].
+///
+/// # Arguments
+///
+/// * `ct` - The ciphertext as a slice of bytes.
+/// * `sk` - The secret key as a slice of bytes.
+/// * `context` - The application context used by the sender.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if the input sizes are incorrect.
+pub fn decapsulate_with_context(
+    ct: &[u8],
+    sk: &[u8],
+    context: &[u8],
+) -> Decapsulated {
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Decapsulate);
+    if ct.len() != KYBER_CIPHERTEXT_BYTES
+        || sk.len() != KYBER_SECRET_KEY_BYTES
+    {
+        return Err(KyberLibError::InvalidInput);
+    }
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    let _ =
+        decrypt_message_with_context(&mut ss, ct, sk, Some(context));
+    Ok(ss)
+}
+
+/// Stretches a shared secret to any length with SHAKE256.
+///
+/// Both parties calling this with the same shared secret and the same
+/// output length obtain identical key material, so protocols needing
+/// e.g. 64 bytes can derive them from the standard 32-byte exchange
+/// without changing what is sent on the wire.
+///
+/// # Arguments
+///
+/// * `ss` - The shared secret from `encapsulate` or `decapsulate`.
+/// * `out` - The output buffer, filled entirely.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss1) = encapsulate(&keys.public, &mut rng)?;
+/// let ss2 = decapsulate(&ct, &keys.secret)?;
+/// let mut okm1 = [0u8; 64];
+/// let mut okm2 = [0u8; 64];
+/// derive_shared_secret(&ss1, &mut okm1);
+/// derive_shared_secret(&ss2, &mut okm2);
+/// assert_eq!(okm1, okm2);
+/// # Ok(())}
+/// ```
+pub fn derive_shared_secret(ss: &SharedSecret, out: &mut [u8]) {
+    let outlen = out.len();
+    shake256(out, outlen, ss, ss.len());
+}
+
+/// Derives several independent keys from one shared secret.
+///
+/// Each `out[i]` is filled with SHAKE256 of `ss || labels[i]`, so keys
+/// with distinct labels are independent and the same label always
+/// yields the same key. Outputs may have any length, e.g. an encryption
+/// key, a MAC key and an IV in one call.
+///
+/// # Panics
+///
+/// Panics if `labels` and `out` have different lengths.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (_, ss) = encapsulate(&keys.public, &mut rng)?;
+/// let mut enc_key = [0u8; 32];
+/// let mut mac_key = [0u8; 32];
+/// let mut iv = [0u8; 12];
+/// derive_keys(
+///     &ss,
+///     &[b"enc", b"mac", b"iv"],
+///     &mut [&mut enc_key, &mut mac_key, &mut iv],
+/// );
+/// assert_ne!(enc_key, mac_key);
+/// # Ok(())}
+/// ```
+pub fn derive_keys(
+    ss: &SharedSecret,
+    labels: &[&[u8]],
+    out: &mut [&mut [u8]],
+) {
+    assert_eq!(labels.len(), out.len(), "derive_keys length mismatch");
+    for (label, key) in labels.iter().zip(out.iter_mut()) {
+        let keylen = key.len();
+        shake256_concat(key, keylen, &[ss, label]);
+    }
+}
+
+/// Expands a shared secret with HKDF-SHA256 (RFC 5869).
+///
+/// The shared secret is used as the input keying material with no
+/// salt, and `out` is filled with the expansion under `info`. A
+/// `SharedSecret` is already a `[u8; 32]`, so this matches what
+/// `Hkdf::<Sha256>::new(None, &ss).expand(info, out)` from the `hkdf`
+/// crate produces, for callers who would rather not depend on it.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidLength` if `out` is longer than
+/// 8160 bytes (255 SHA-256 blocks), the most HKDF can expand to.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss1) = encapsulate(&keys.public, &mut rng)?;
+/// let ss2 = decapsulate(&ct, &keys.secret)?;
+/// let mut okm1 = [0u8; 42];
+/// let mut okm2 = [0u8; 42];
+/// expand_hkdf(&ss1, b"session key", &mut okm1)?;
+/// expand_hkdf(&ss2, b"session key", &mut okm2)?;
+/// assert_eq!(okm1, okm2);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "hkdf")]
+pub fn expand_hkdf(
+    ss: &SharedSecret,
+    info: &[u8],
+    out: &mut [u8],
+) -> Result<(), KyberLibError> {
+    hkdf::Hkdf::<sha2::Sha256>::new(None, ss)
+        .expand(info, out)
+        .map_err(|_| KyberLibError::InvalidLength)
+}
+
+/// Returns a SHAKE256 reader over a shared secret, for pulling an
+/// unbounded keystream lazily.
+///
+/// The stream is `SHAKE256(ss)`, so its first bytes equal a one-shot
+/// `shake256` of the secret of the same length. Reading in several
+/// calls gives the same bytes as one read of the combined length.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss) = encapsulate(&keys.public, &mut rng)?;
+/// let mut alice = xof_reader(&ss);
+/// let mut bob = xof_reader(&decapsulate(&ct, &keys.secret)?);
+/// let mut block = [0u8; 1000];
+/// alice.read(&mut block);
+/// assert!(block.iter().copied().eq(bob.by_ref().take(1000)));
+/// # Ok(())}
+/// ```
+pub fn xof_reader(ss: &SharedSecret) -> KyberXofReader {
+    let mut state = KeccakState::new();
+    keccak_absorb_once(&mut state.s, SHAKE256_RATE, ss, ss.len(), 0x1F);
+    state.pos = SHAKE256_RATE;
+    KyberXofReader { state }
+}
+
+/// Incremental SHAKE256 output seeded by a shared secret.
+///
+/// Created by [`xof_reader`]. Bytes can be pulled into a buffer with
+/// [`read`](Self::read) or one at a time as an `Iterator`. The state is
+/// wiped on drop and is not shown by `Debug`.
+pub struct KyberXofReader {
+    state: KeccakState,
+}
+
+impl KyberXofReader {
+    /// Fills `out` with the next bytes of the stream.
+    pub fn read(&mut self, out: &mut [u8]) {
+        let len = out.len();
+        self.state.pos = keccak_squeeze(
+            out,
+            len,
+            &mut self.state.s,
+            self.state.pos,
+            SHAKE256_RATE,
+        );
+    }
+}
+
+impl Iterator for KyberXofReader {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let mut byte = [0u8; 1];
+        self.read(&mut byte);
+        Some(byte[0])
+    }
+}
+
+impl Drop for KyberXofReader {
+    fn drop(&mut self) {
+        wipe(&mut self.state.s);
+    }
+}
+
+// Keeps the keystream state out of logs and panic messages
+impl core::fmt::Debug for KyberXofReader {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        f.write_str("KyberXofReader(..)")
+    }
+}
+
+/// Checks in constant time whether a shared secret is all zeros.
+///
+/// An all-zero secret usually means a step was skipped, e.g. a key
+/// exchange that was never confirmed. Every byte is OR-ed together and
+/// the result compared with `subtle`, so unlike `ss == [0u8; 32]` the
+/// time taken does not depend on where the first nonzero byte is.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (_, ss) = encapsulate(&keys.public, &mut rng)?;
+/// assert!(!shared_secret_is_zero(&ss));
+/// assert!(shared_secret_is_zero(&Uake::new().shared_secret));
+/// # Ok(())}
+/// ```
+pub fn shared_secret_is_zero(ss: &SharedSecret) -> bool {
+    let acc = ss.iter().fold(0u8, |acc, &b| acc | b);
+    acc.ct_eq(&0).into()
+}
+
+/// Compares two secret keys in constant time.
+///
+/// Use this instead of `==` when checking secret material, e.g. that a
+/// re-derived key matches the stored one: the time taken does not
+/// depend on where the keys first differ. Keys of different lengths
+/// compare unequal.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = derive(&[1u8; 64])?;
+/// assert!(secret_key_ct_eq(&keys.secret, &derive(&[1u8; 64])?.secret));
+/// assert!(!secret_key_ct_eq(&keys.secret, &derive(&[2u8; 64])?.secret));
+/// # Ok(())}
+/// ```
+pub fn secret_key_ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Checks whether two public keys are the same key, e.g. when a peer
+/// reconnects and its key is compared with a pinned copy.
+///
+/// Keys of different lengths, such as keys of two security levels,
+/// never match. Equal-length keys are compared in constant time.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let pinned = derive(&[1u8; 64])?.public;
+/// assert!(public_keys_match(&pinned, &derive(&[1u8; 64])?.public));
+/// assert!(!public_keys_match(&pinned, &pinned[1..]));
+/// # Ok(())}
+/// ```
+pub fn public_keys_match(a: &[u8], b: &[u8]) -> bool {
+    ct_slices_equal(a, b)
+}
+
+/// Checks whether two ciphertexts are byte-equal, i.e. come from the
+/// same encapsulation, e.g. for a caching proxy keyed on ciphertexts.
+///
+/// No secret key is needed. Ciphertexts of different lengths never
+/// match, and equal-length ones are compared in constant time, unlike
+/// `==`, which stops at the first differing byte.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, _) = encapsulate(&keys.public, &mut rng)?;
+/// let (other, _) = encapsulate(&keys.public, &mut rng)?;
+/// let cached = ct;
+/// assert!(ciphertexts_equal(&ct, &cached));
+/// assert!(!ciphertexts_equal(&ct, &other));
+/// # Ok(())}
+/// ```
+pub fn ciphertexts_equal(a: &[u8], b: &[u8]) -> bool {
+    ct_slices_equal(a, b)
+}
+
+// Compares equal-length slices in constant time; the length itself is
+// public, so slices of different lengths return false straight away
+fn ct_slices_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && bool::from(a.ct_eq(b))
+}
+
+/// Returns a short fingerprint of a public key for comparison by eye.
+///
+/// The fingerprint is the first 8 bytes of SHA3-256(pk) as
+/// colon-separated lowercase hex, e.g. `3f:a2:07:9c:5e:11:d0:84`, in
+/// the style of SSH key fingerprints. It is meant for humans checking
+/// that two keys agree; 64 bits is too short to rely on against an
+/// attacker, who can search for a colliding key, so pin the full key
+/// with [`public_keys_match`] in code.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = derive(&[1u8; 64])?;
+/// let fingerprint = public_key_fingerprint(&keys.public);
+/// assert_eq!(fingerprint.len(), 23);
+/// assert_eq!(fingerprint, public_key_fingerprint(&keys.public));
+/// # Ok(())}
+/// ```
+pub fn public_key_fingerprint(pk: &[u8]) -> String {
+    let mut hash = [0u8; 32];
+    sha3_256(&mut hash, pk, pk.len());
+    let mut out = String::with_capacity(23);
+    for (i, byte) in hash[..8].iter().enumerate() {
+        if i > 0 {
+            out.push(':');
+        }
+        let _ = write!(out, "{:02x}", byte);
+    }
+    out
+}
+
+/// Returns the matrix seed `rho` embedded in a public key.
+///
+/// A public key is the encoded vector `t` followed by the 32-byte seed
+/// that expands the public matrix `A`. This returns that seed, for
+/// interop debugging and for protocols that share `rho` between keys.
+///
+/// # Panics
+///
+/// Panics if `pk` is not `KYBER_PUBLIC_KEY_BYTES` long.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = keypair(&mut rand::thread_rng())?;
+/// let rho = public_key_seed(&keys.public);
+/// assert_eq!(rho[..], keys.public[KYBER_PUBLIC_KEY_BYTES - 32..]);
+/// # Ok(())}
+/// ```
+pub fn public_key_seed(pk: &[u8]) -> [u8; KYBER_SYM_BYTES] {
+    assert_eq!(
+        pk.len(),
+        KYBER_PUBLIC_KEY_BYTES,
+        "public_key_seed: wrong public key length"
+    );
+    let mut rho = [0u8; KYBER_SYM_BYTES];
+    rho.copy_from_slice(&pk[KYBER_POLYVEC_BYTES..]);
+    rho
+}
+
+// RFC 4648 base32 alphabet
+#[cfg(feature = "encoding")]
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+// Length of an unpadded base32 public key
+#[cfg(feature = "encoding")]
+const BASE32_PUBLIC_KEY_CHARS: usize =
+    (KYBER_PUBLIC_KEY_BYTES * 8).div_ceil(5);
+
+/// Encodes a public key as unpadded, uppercase RFC 4648 base32.
+///
+/// The output only uses `A`-`Z` and `2`-`7`, all of which fit the
+/// compact alphanumeric mode of QR codes.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = derive(&[1u8; 64])?;
+/// let text = encode_base32(&keys.public);
+/// assert_eq!(decode_base32(&text)?, keys.public);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "encoding")]
+pub fn encode_base32(pk: &PublicKey) -> String {
+    let mut out = String::with_capacity(BASE32_PUBLIC_KEY_CHARS);
+    let mut acc = 0u16;
+    let mut bits = 0;
+    for &byte in pk.iter() {
+        acc = (acc << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(
+                BASE32_ALPHABET[usize::from((acc >> bits) & 31)]
+                    as char,
+            );
+        }
+    }
+    if bits > 0 {
+        out.push(
+            BASE32_ALPHABET[usize::from((acc << (5 - bits)) & 31)]
+                as char,
+        );
+    }
+    out
+}
+
+/// Decodes a public key written by [`encode_base32`].
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidLength` if `s` does not encode exactly
+/// `KYBER_PUBLIC_KEY_BYTES` bytes, and `KyberLibError::InvalidInput` on
+/// any character outside the uppercase base32 alphabet (including `=`
+/// padding) or nonzero trailing bits.
+#[cfg(feature = "encoding")]
+pub fn decode_base32(s: &str) -> Result<PublicKey, KyberLibError> {
+    if s.len() != BASE32_PUBLIC_KEY_CHARS {
+        return Err(KyberLibError::InvalidLength);
+    }
+    let mut pk = [0u8; KYBER_PUBLIC_KEY_BYTES];
+    let mut acc = 0u16;
+    let mut bits = 0;
+    let mut i = 0;
+    for c in s.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return Err(KyberLibError::InvalidInput),
+        };
+        acc = (acc << 5) | u16::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            pk[i] = (acc >> bits) as u8;
+            i += 1;
+        }
+    }
+    // Leftover bits are padding and must be zero
+    if acc & ((1 << bits) - 1) != 0 {
+        return Err(KyberLibError::InvalidInput);
+    }
+    Ok(pk)
+}
+
+/// Flags public keys that are obviously unusable.
+///
+/// Returns `true` if `pk` has the wrong length, is all zeros, or
+/// encodes a polynomial coefficient outside `0..KYBER_Q` (a check the
+/// key generator never fails). A `false` result does not prove the key
+/// was honestly generated, it only rules out degenerate input before
+/// trusting a peer key. The 32-byte matrix seed at the end of the key
+/// can hold any value and is not checked.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// assert!(!is_trivial_public_key(&keys.public));
+/// assert!(is_trivial_public_key(&[0u8; KYBER_PUBLIC_KEY_BYTES]));
+/// # Ok(())}
+/// ```
+pub fn is_trivial_public_key(pk: &[u8]) -> bool {
+    if pk.len() != KYBER_PUBLIC_KEY_BYTES || pk.iter().all(|&b| b == 0)
+    {
+        return true;
+    }
+    // Each 3 bytes of the polynomial vector pack two 12-bit coefficients
+    pk[..KYBER_POLYVEC_BYTES].chunks_exact(3).any(|c| {
+        let a = u16::from(c[0]) | (u16::from(c[1] & 0x0f) << 8);
+        let b = u16::from(c[1] >> 4) | (u16::from(c[2]) << 4);
+        usize::from(a) >= KYBER_Q || usize::from(b) >= KYBER_Q
+    })
+}
+
+/// Checks that `ct` is structurally a ciphertext for the compiled level.
+///
+/// This is a cheap filter a server can run before decapsulating. Only
+/// the length can be rejected: the compressed coefficients are packed
+/// in `du` and `dv` bits, and since `2^du < q` every bit pattern
+/// decompresses to a coefficient in range. A length-correct ciphertext
+/// of random bytes is therefore well formed, and decapsulates to the
+/// implicit-rejection secret as usual.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, _) = encapsulate(&keys.public, &mut rng)?;
+/// assert!(ciphertext_is_well_formed(&ct));
+/// assert!(!ciphertext_is_well_formed(&ct[1..]));
+/// # Ok(())}
+/// ```
+pub fn ciphertext_is_well_formed(ct: &[u8]) -> bool {
+    // Every du- and dv-bit value decompresses into [0, q), so there is
+    // no coefficient range to check
+    ct.len() == KYBER_CIPHERTEXT_BYTES
+}
+
+/// A public key with its matrix `A` expanded once for repeated use.
+///
+/// Every encapsulation re-expands the public matrix from the seed in
+/// the public key. When the same key is encapsulated to many times,
+/// e.g. a server talking to a fixed client, preparing it once and
+/// calling [`encapsulate_prepared`] skips that work. The ciphertexts
+/// and shared secrets are identical to those of [`encapsulate`].
+#[derive(Clone)]
+pub struct PreparedPublicKey {
+    public: PublicKey,
+    at: PublicMatrix,
+}
+
+impl PreparedPublicKey {
+    /// Expands the matrix of a public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if `pk` is not
+    /// `KYBER_PUBLIC_KEY_BYTES` long.
+    ///
+    /// ### Example
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = keypair(&mut rng)?;
+    /// let prepared = PreparedPublicKey::new(&keys.public)?;
+    /// for _ in 0..3 {
+    ///     let (ct, ss1) = encapsulate_prepared(&prepared, &mut rng)?;
+    ///     assert_eq!(decapsulate(&ct, &keys.secret)?, ss1);
+    /// }
+    /// # Ok(())}
+    /// ```
+    pub fn new(pk: &[u8]) -> Result<Self, KyberLibError> {
+        if pk.len() != KYBER_PUBLIC_KEY_BYTES {
+            return Err(KyberLibError::InvalidInput);
+        }
+        let mut public = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        public.copy_from_slice(pk);
+        let mut at = [Polyvec::new(); KYBER_SECURITY_PARAMETER];
+        indcpa_expand_at(&mut at, &public);
+        Ok(Self { public, at })
+    }
+
+    /// Returns the public key this was prepared from.
+    pub fn public(&self) -> &PublicKey {
+        &self.public
+    }
+}
+
+impl core::fmt::Debug for PreparedPublicKey {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        f.debug_struct("PreparedPublicKey")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encapsulates a public key prepared with [`PreparedPublicKey::new`].
+///
+/// # Arguments
+///
+/// * `prepared` - The prepared public key.
+/// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if an error occurs during encapsulation.
+pub fn encapsulate_prepared<R>(
+    prepared: &PreparedPublicKey,
+    rng: &mut R,
+) -> Encapsulated
+where
+    R: CryptoRng + RngCore,
+{
+    #[cfg(feature = "metrics")]
+    let _timer = Timer::start(Operation::Encapsulate);
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    let _ct_hash = encrypt_message_prepared(
+        &mut ct,
+        &mut ss,
+        &prepared.public,
+        &prepared.at,
+        rng,
+        None,
+        None,
+    )?;
+    Ok((ct, ss))
+}
+
+/// A secret key with its public matrix `A` expanded once for repeated
+/// decapsulation.
+///
+/// Every decapsulation re-encrypts the decrypted message to check the
+/// ciphertext, which means re-expanding the public matrix from the seed
+/// embedded in the secret key. A server decapsulating many ciphertexts
+/// under one long-lived key can build a context once and skip that
+/// work. Shared secrets are identical to those of [`decapsulate`].
+///
+/// The context keeps a copy of the secret key, which is wiped on drop.
+pub struct DecapsulationContext {
+    secret: SecretKey,
+    at: PublicMatrix,
+}
+
+impl DecapsulationContext {
+    /// Copies the secret key and expands the matrix of its public key.
+    ///
+    /// ### Example
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = keypair(&mut rng)?;
+    /// let ctx = DecapsulationContext::new(&keys.secret);
+    /// for _ in 0..3 {
+    ///     let (ct, ss) = encapsulate(&keys.public, &mut rng)?;
+    ///     assert_eq!(ctx.decapsulate(&ct)?, ss);
+    /// }
+    /// # Ok(())}
+    /// ```
+    pub fn new(sk: &SecretKey) -> Self {
+        let mut at = [Polyvec::new(); KYBER_SECURITY_PARAMETER];
+        indcpa_expand_at(&mut at, &sk[KYBER_INDCPA_SECRET_KEY_BYTES..]);
+        Self { secret: *sk, at }
+    }
+
+    /// Decapsulates a ciphertext under the context's secret key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if `ct` is not
+    /// `KYBER_CIPHERTEXT_BYTES` long.
+    pub fn decapsulate(&self, ct: &[u8]) -> Decapsulated {
+        #[cfg(feature = "metrics")]
+        let _timer = Timer::start(Operation::Decapsulate);
+        if ct.len() != KYBER_CIPHERTEXT_BYTES {
+            return Err(KyberLibError::InvalidInput);
+        }
+        let pk = &self.secret[KYBER_INDCPA_SECRET_KEY_BYTES..]
+            [..KYBER_INDCPA_PUBLIC_KEY_BYTES];
+        let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+        let _ = decrypt_message_prepared(
+            &mut ss,
+            ct,
+            &self.secret,
+            pk,
+            Some(&self.at),
+            None,
+        );
+        Ok(ss)
+    }
+}
+
+impl Drop for DecapsulationContext {
+    fn drop(&mut self) {
+        wipe(&mut self.secret);
+    }
+}
+
+// Keeps the secret key out of logs and panic messages
+impl core::fmt::Debug for DecapsulationContext {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        f.write_str("DecapsulationContext(..)")
+    }
+}
+
+/// Magic bytes opening a blob produced by [`Keypair::to_bytes`].
+pub const KEYPAIR_MAGIC: [u8; 4] = *b"KYBK";
+
+/// Format version written by [`Keypair::to_bytes`].
+pub const KEYPAIR_VERSION: u8 = 1;
+
+// Magic, version and level byte preceding the keys
+const KEYPAIR_HEADER_BYTES: usize = KEYPAIR_MAGIC.len() + 2;
+
+/// A public/secret keypair for use with Kyber.
+///
+/// Byte lengths of the keys are determined by the security level chosen.
+///
+/// With the `zeroize` feature the keypair is wiped on drop and is
+/// therefore `Clone` but not `Copy`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(not(feature = "zeroize"), derive(Copy))]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
+pub struct Keypair {
+    /// The public key.
+    pub public: PublicKey,
+    /// The secret key.
+    pub secret: SecretKey,
+}
+
+impl Keypair {
+    /// Securely generates a new keypair.
+    ///
+    /// This function generates a new Kyber key pair and returns it as a `Keypair` struct.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
+    ///
+    /// ### Example
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = Keypair::generate(&mut rng)?;
+    /// # let empty_keys = Keypair{
+    ///   public: [0u8; KYBER_PUBLIC_KEY_BYTES], secret: [0u8; KYBER_SECRET_KEY_BYTES]
+    /// };
+    /// # assert!(empty_keys != keys);
+    /// # Ok(()) }
+    /// ```
+    pub fn generate<R: CryptoRng + RngCore>(
+        rng: &mut R,
+    ) -> Result<Keypair, KyberLibError> {
+        keypair(rng)
+    }
+
+    /// Explicitly exposes the secret key
+    ///```
+    /// use kyberlib::*;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let keys = Keypair::generate(&mut rng);
+    /// let binding = keys.expect("Exposed secret key");
+    /// let secret = binding.expose_secret();
+    /// assert!(secret.len() == KYBER_SECRET_KEY_BYTES);
+    /// assert!(secret.len() != 0);
+    /// ```
+    pub fn expose_secret(&self) -> &SecretKey {
+        &self.secret
+    }
+
+    /// Zeroes both keys now, without waiting for the keypair to drop.
+    ///
+    /// The keypair stays usable afterwards but holds all-zero keys, so
+    /// a secret can be wiped at a precise point while the variable is
+    /// still in scope.
+    ///
+    /// ### Example
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut keys = keypair(&mut rand::thread_rng())?;
+    /// keys.wipe();
+    /// assert!(keys.secret.iter().all(|&b| b == 0));
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "zeroize")]
+    pub fn wipe(&mut self) {
+        self.zeroize();
+    }
+
+    /// Imports a keypair from existing public and secret key arrays.
+    ///
+    /// This function imports a keypair from existing public and secret key arrays and returns it as a `Keypair` struct.
+    ///
+    /// # Arguments
+    ///
+    /// * `public` - A mutable reference to a `[u8; KYBER_PUBLIC_KEY_BYTES]` array representing the public key.
+    /// * `secret` - A mutable reference to a `[u8; KYBER_SECRET_KEY_BYTES]` array representing the secret key.
+    /// * `rng` - The random number generator implementing the `RngCore` and `CryptoRng` traits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = keypair(&mut rng)?;
+    /// let mut public_key = keys.public;
+    /// let mut secret_key = keys.secret;
+    /// let imported = Keypair::import(&mut public_key, &mut secret_key, &mut rng)?;
+    /// assert_eq!(imported, keys);
+    /// # Ok(()) }
+    /// ```
+    pub fn import<R: CryptoRng + RngCore>(
+        public: &mut [u8; KYBER_PUBLIC_KEY_BYTES],
+        secret: &mut [u8; KYBER_SECRET_KEY_BYTES],
+        rng: &mut R,
+    ) -> Result<Keypair, KyberLibError> {
+        keypairfrom(public, secret, rng)
+    }
+
+    /// Imports a keypair from existing public and secret key bytes
+    /// without verifying that they belong together.
+    ///
+    /// Unlike [`Keypair::import`], no encapsulate/decapsulate round is
+    /// performed and no RNG is required; only the lengths of the inputs
+    /// are checked. The caller vouches that `secret` is the secret key
+    /// matching `public`, e.g. because the pair was validated when it
+    /// was first written to trusted storage. A mismatched pair is not
+    /// detected here and will silently produce unusable shared secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `public` - The public key as a slice of `KYBER_PUBLIC_KEY_BYTES` bytes.
+    /// * `secret` - The secret key as a slice of `KYBER_SECRET_KEY_BYTES` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if either key has an incorrect length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = keypair(&mut rng)?;
+    /// let imported = Keypair::import_unchecked(&keys.public, &keys.secret)?;
+    /// assert_eq!(imported, keys);
+    /// # Ok(()) }
+    /// ```
+    pub fn import_unchecked(
+        public: &[u8],
+        secret: &[u8],
+    ) -> Result<Keypair, KyberLibError> {
+        if public.len() != KYBER_PUBLIC_KEY_BYTES
+            || secret.len() != KYBER_SECRET_KEY_BYTES
+        {
+            return Err(KyberLibError::InvalidInput);
+        }
+        let mut keys = Keypair {
+            public: [0u8; KYBER_PUBLIC_KEY_BYTES],
+            secret: [0u8; KYBER_SECRET_KEY_BYTES],
+        };
+        keys.public.copy_from_slice(public);
+        keys.secret.copy_from_slice(secret);
+        Ok(keys)
+    }
+
+    /// Reads a keypair serialized as `public || secret` from a reader.
+    ///
+    /// Exactly `KYBER_PUBLIC_KEY_BYTES + KYBER_SECRET_KEY_BYTES` bytes are
+    /// read straight into the keypair without intermediate allocations.
+    /// As with [`Keypair::import_unchecked`], the keys are not checked to
+    /// belong together.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidLength` if the reader ends before a
+    /// full keypair has been read, or `KyberLibError::InvalidInput` on any
+    /// other I/O error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = keypair(&mut rng)?;
+    /// let mut bytes = Vec::new();
+    /// keys.write_to(&mut bytes).unwrap();
+    /// let read = Keypair::read_from(&mut bytes.as_slice())?;
+    /// assert_eq!(read, keys);
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn read_from<R: std::io::Read>(
+        r: &mut R,
+    ) -> Result<Keypair, KyberLibError> {
+        let mut keys = Keypair {
+            public: [0u8; KYBER_PUBLIC_KEY_BYTES],
+            secret: [0u8; KYBER_SECRET_KEY_BYTES],
+        };
+        r.read_exact(&mut keys.public)
+            .and_then(|_| r.read_exact(&mut keys.secret))
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => {
+                    KyberLibError::InvalidLength
+                }
+                _ => KyberLibError::InvalidInput,
+            })?;
+        Ok(keys)
+    }
+
+    /// Writes the keypair to a writer as `public || secret`, the format
+    /// read by [`Keypair::read_from`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any I/O error raised by the writer.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        w: &mut W,
+    ) -> std::io::Result<()> {
+        w.write_all(&self.public)?;
+        w.write_all(&self.secret)
+    }
+
+    /// Serializes the keypair into a single self-describing blob.
+    ///
+    /// The layout is `KEYPAIR_MAGIC || KEYPAIR_VERSION || level ||
+    /// public || secret`, where `level` is the module rank k of the
+    /// compiled build (2, 3 or 4). The blob holds the secret key, so
+    /// wipe it once it has been stored.
+    ///
+    /// # Example
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let keys = keypair(&mut rng)?;
+    /// let blob = keys.to_bytes();
+    /// assert_eq!(Keypair::from_bytes(&blob)?, keys);
+    /// # Ok(()) }
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(
+            KEYPAIR_HEADER_BYTES
+                + KYBER_PUBLIC_KEY_BYTES
+                + KYBER_SECRET_KEY_BYTES,
+        );
+        out.extend_from_slice(&KEYPAIR_MAGIC);
+        out.push(KEYPAIR_VERSION);
+        out.push(KYBER_SECURITY_PARAMETER as u8);
+        out.extend_from_slice(&self.public);
+        out.extend_from_slice(&self.secret);
+        out
+    }
+
+    /// Parses a blob produced by [`Keypair::to_bytes`].
+    ///
+    /// As with [`Keypair::import_unchecked`], the keys are not checked
+    /// to belong together.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if the magic bytes,
+    /// version or length are wrong, and `KyberLibError::InvalidKey` if
+    /// the blob was written by a build of a different security level.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Keypair, KyberLibError> {
+        if bytes.len() < KEYPAIR_HEADER_BYTES
+            || bytes[..KEYPAIR_MAGIC.len()] != KEYPAIR_MAGIC
+            || bytes[KEYPAIR_MAGIC.len()] != KEYPAIR_VERSION
+        {
+            return Err(KyberLibError::InvalidInput);
+        }
+        if usize::from(bytes[KEYPAIR_HEADER_BYTES - 1])
+            != KYBER_SECURITY_PARAMETER
+        {
+            return Err(KyberLibError::InvalidKey);
+        }
+        let keys = &bytes[KEYPAIR_HEADER_BYTES..];
+        if keys.len() != KYBER_PUBLIC_KEY_BYTES + KYBER_SECRET_KEY_BYTES
+        {
+            return Err(KyberLibError::InvalidInput);
+        }
+        let (public, secret) = keys.split_at(KYBER_PUBLIC_KEY_BYTES);
+        Keypair::import_unchecked(public, secret)
+    }
+}
+
+/// Decapsulates a stream of concatenated ciphertexts one at a time.
+///
+/// Each call to `next()` reads `KYBER_CIPHERTEXT_BYTES` from the reader
+/// and yields the decapsulated shared secret, so large batches never
+/// need to fit in memory. The stream ends cleanly at end of input on a
+/// ciphertext boundary. A partial trailing ciphertext, or any read
+/// error, yields a single `Err(KyberLibError::InvalidInput)` and ends
+/// the stream.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct1, ss1) = encapsulate(&keys.public, &mut rng)?;
+/// let (ct2, ss2) = encapsulate(&keys.public, &mut rng)?;
+/// let batch = [ct1, ct2].concat();
+/// let secrets: Result<Vec<_>, _> =
+///     DecapStream::new(batch.as_slice(), &keys.secret).collect();
+/// assert_eq!(secrets?, vec![ss1, ss2]);
+/// # Ok(()) }
+/// ```
+#[cfg(feature = "std")]
+pub struct DecapStream<'a, R> {
+    reader: R,
+    sk: &'a [u8],
+    done: bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: std::io::Read> DecapStream<'a, R> {
+    /// Creates a stream decapsulating ciphertexts read from `reader`
+    /// with the secret key `sk`.
+    pub fn new(reader: R, sk: &'a [u8]) -> Self {
+        DecapStream {
+            reader,
+            sk,
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Iterator for DecapStream<'_, R> {
+    type Item = Result<SharedSecret, KyberLibError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+        let mut filled = 0;
+        while filled < ct.len() {
+            match self.reader.read(&mut ct[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e)
+                    if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => {
+                    self.done = true;
+                    return Some(Err(KyberLibError::InvalidInput));
+                }
+            }
+        }
+        match filled {
+            0 => {
+                self.done = true;
+                None
+            }
+            KYBER_CIPHERTEXT_BYTES => Some(decapsulate(&ct, self.sk)),
+            _ => {
+                self.done = true;
+                Some(Err(KyberLibError::InvalidInput))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> core::fmt::Debug for DecapStream<'_, R> {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        f.debug_struct("DecapStream")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A zero-initialised landing buffer for secret key bytes that is
+/// wiped on drop.
+///
+/// Read a stored secret key straight into this buffer (it derefs to a
+/// mutable slice) instead of a plain local array, then turn it into a
+/// `Keypair` with [`SecretKeyBuf::into_keypair`].
+///
+/// # Example
+///
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let mut buf = SecretKeyBuf::new();
+/// buf.copy_from_slice(&keys.secret);
+/// let imported = buf.into_keypair(&keys.public)?;
+/// assert_eq!(imported, keys);
+/// # Ok(()) }
+/// ```
+#[cfg(feature = "zeroize")]
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKeyBuf([u8; KYBER_SECRET_KEY_BYTES]);
+
+#[cfg(feature = "zeroize")]
+impl SecretKeyBuf {
+    /// Creates a new, all-zero buffer.
+    pub fn new() -> Self {
+        SecretKeyBuf([0u8; KYBER_SECRET_KEY_BYTES])
+    }
+
+    /// Builds a `Keypair` from the buffered secret key and its public
+    /// key using [`Keypair::import_unchecked`]. The buffer is wiped
+    /// when it is dropped at the end of this call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if `public` has an incorrect length.
+    pub fn into_keypair(
+        self,
+        public: &[u8],
+    ) -> Result<Keypair, KyberLibError> {
+        Keypair::import_unchecked(public, &self.0)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Default for SecretKeyBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl core::ops::Deref for SecretKeyBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl core::ops::DerefMut for SecretKeyBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+// Keeps the secret key out of logs and panic messages
+#[cfg(feature = "zeroize")]
+impl core::fmt::Debug for SecretKeyBuf {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        f.write_str("SecretKeyBuf(..)")
+    }
+}
+
+struct DummyRng {}
+
+impl CryptoRng for DummyRng {}
+
+impl RngCore for DummyRng {
+    fn next_u32(&mut self) -> u32 {
+        panic!()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        panic!()
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        _dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        panic!()
+    }
+
+    fn fill_bytes(&mut self, _dest: &mut [u8]) {
+        panic!()
+    }
+}
+
+/// Deterministically derive a keypair from a seed as specified in draft-schwabe-cfrg-kyber.
+///
+/// This function deterministically derives a key pair from a seed and returns it as a `Keypair` struct.
+///
+/// # Arguments
+///
+/// * `seed` - The seed as a slice of bytes.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if the seed length is incorrect.
+pub fn derive(seed: &[u8]) -> Result<Keypair, KyberLibError> {
+    let mut public = [0u8; KYBER_PUBLIC_KEY_BYTES];
+    let mut secret = [0u8; KYBER_SECRET_KEY_BYTES];
+    let mut _rng = DummyRng {};
+    if seed.len() != KYBER_DERIVE_SEED_BYTES {
+        return Err(KyberLibError::InvalidInput);
+    }
+    generate_key_pair(
+        &mut public,
         &mut secret,
         &mut _rng,
-        Some((&seed[..32], &seed[32..])),
+        Some((&seed[..KYBER_SYM_BYTES], &seed[KYBER_SYM_BYTES..])),
     )?;
     Ok(Keypair { public, secret })
 }
 
+/// Deterministically derive a keypair from a single 32-byte seed.
+///
+/// The seed is expanded with SHAKE256 to the 64 bytes `d || z` that
+/// [`derive`] expects, so wallets can store one 32-byte secret. The
+/// expanded seed is wiped before returning.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if key generation fails.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = derive_from_32(&[9u8; 32])?;
+/// assert_eq!(keys, derive_from_32(&[9u8; 32])?);
+/// # Ok(())}
+/// ```
+pub fn derive_from_32(
+    seed: &[u8; 32],
+) -> Result<Keypair, KyberLibError> {
+    let mut expanded = [0u8; KYBER_DERIVE_SEED_BYTES];
+    shake256(&mut expanded, KYBER_DERIVE_SEED_BYTES, seed, 32);
+    let keys = derive(&expanded);
+    wipe(&mut expanded);
+    keys
+}
+
+/// Deterministically derive the keypair for a rotation epoch.
+///
+/// The fresh 64-byte seed is SHAKE256 of `seed || epoch`, with `epoch`
+/// encoded as 8 little-endian bytes, and is passed to [`derive`]. Both
+/// sides holding `seed` can compute the rotated keys for any epoch
+/// without a new handshake. Intermediate buffers are wiped.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if key generation fails.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let seed = [5u8; 64];
+/// assert_eq!(rotate(&seed, 1)?, rotate(&seed, 1)?);
+/// assert_ne!(rotate(&seed, 1)?.public, rotate(&seed, 2)?.public);
+/// # Ok(())}
+/// ```
+pub fn rotate(
+    seed: &[u8; KYBER_DERIVE_SEED_BYTES],
+    epoch: u64,
+) -> Result<Keypair, KyberLibError> {
+    let mut input = [0u8; KYBER_DERIVE_SEED_BYTES + 8];
+    input[..KYBER_DERIVE_SEED_BYTES].copy_from_slice(seed);
+    input[KYBER_DERIVE_SEED_BYTES..]
+        .copy_from_slice(&epoch.to_le_bytes());
+    let mut rotated = [0u8; KYBER_DERIVE_SEED_BYTES];
+    shake256(
+        &mut rotated,
+        KYBER_DERIVE_SEED_BYTES,
+        &input,
+        input.len(),
+    );
+    wipe(&mut input);
+    let keys = derive(&rotated);
+    wipe(&mut rotated);
+    keys
+}
+
+// Derives item `index` of a seeded batch: SHAKE256(master || index)
+// expanded to the 64 bytes `derive` expects
+fn batch_item(master: &[u8; 32], index: usize) -> Keypair {
+    let mut input = [0u8; 40];
+    input[..32].copy_from_slice(master);
+    input[32..].copy_from_slice(&(index as u64).to_le_bytes());
+    let mut seed = [0u8; KYBER_DERIVE_SEED_BYTES];
+    shake256(&mut seed, KYBER_DERIVE_SEED_BYTES, &input, 40);
+    wipe(&mut input);
+    // derive only fails on a seed of the wrong length
+    let keys = derive(&seed).expect("64-byte seed");
+    wipe(&mut seed);
+    keys
+}
+
+/// Deterministically derives `n` keypairs from a 32-byte master seed.
+///
+/// Keypair `i` is [`derive`]d from SHAKE256 of `rng_seed || i`, with
+/// `i` encoded as 8 little-endian bytes, so the same seed always gives
+/// the same batch. Use this for reproducible test fixtures; see
+/// [`keypair_batch_par`] for the multi-threaded version.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// let batch = keypair_batch([3u8; 32], 2);
+/// assert_eq!(batch, keypair_batch([3u8; 32], 2));
+/// assert_ne!(batch[0], batch[1]);
+/// ```
+pub fn keypair_batch(rng_seed: [u8; 32], n: usize) -> Vec<Keypair> {
+    (0..n).map(|i| batch_item(&rng_seed, i)).collect()
+}
+
+/// Generates the same batch as [`keypair_batch`] across the rayon
+/// global thread pool.
+///
+/// Each keypair depends only on the master seed and its index, so the
+/// output is identical to the serial version regardless of the number
+/// of threads.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// let batch = keypair_batch_par([3u8; 32], 16);
+/// assert_eq!(batch, keypair_batch([3u8; 32], 16));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn keypair_batch_par(rng_seed: [u8; 32], n: usize) -> Vec<Keypair> {
+    use rayon::prelude::*;
+    (0..n)
+        .into_par_iter()
+        .map(|i| batch_item(&rng_seed, i))
+        .collect()
+}
+
+/// Derives a keypair like [`derive`], then wipes the caller's seed.
+///
+/// The 64-byte master seed is overwritten with zeros once the keys are
+/// derived, so it does not linger in memory. The wipe uses `zeroize`
+/// with the `zeroize` feature and volatile writes otherwise. The seed
+/// is wiped even if derivation fails.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if key generation fails.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut seed = [7u8; 64];
+/// let keys = derive_and_wipe(&mut seed)?;
+/// assert_eq!(seed, [0u8; 64]);
+/// assert_eq!(keys, derive(&[7u8; 64])?);
+/// # Ok(())}
+/// ```
+pub fn derive_and_wipe(
+    seed: &mut [u8; KYBER_DERIVE_SEED_BYTES],
+) -> Result<Keypair, KyberLibError> {
+    let keys = derive(seed);
+    wipe(seed);
+    keys
+}
+
+/// Deterministically derives only the public key for a seed.
+///
+/// Produces the same public key as [`derive`], for two-phase
+/// provisioning where the secret key is regenerated from the seed
+/// inside a secure enclave and only the public key leaves this
+/// process. The secret key is computed in a local buffer and wiped
+/// before returning.
+///
+/// # Errors
+///
+/// Returns a `KyberLibError` if key generation fails.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let seed = [42u8; 64];
+/// let public = generate_public_only(&seed)?;
+/// assert_eq!(public, derive(&seed)?.public);
+/// # Ok(())}
+/// ```
+pub fn generate_public_only(
+    seed: &[u8; KYBER_DERIVE_SEED_BYTES],
+) -> Result<PublicKey, KyberLibError> {
+    let mut public = [0u8; KYBER_PUBLIC_KEY_BYTES];
+    let mut secret = [0u8; KYBER_SECRET_KEY_BYTES];
+    let result = generate_key_pair(
+        &mut public,
+        &mut secret,
+        &mut DummyRng {},
+        Some((&seed[..KYBER_SYM_BYTES], &seed[KYBER_SYM_BYTES..])),
+    );
+    wipe(&mut secret);
+    result.map(|()| public)
+}
+
+// Overwrites secret material, bytes or Keccak lanes, with zeros in a
+// way the compiler keeps
+pub(crate) fn wipe<T: DefaultIsZeroes>(buf: &mut [T]) {
+    #[cfg(feature = "zeroize")]
+    buf.zeroize();
+    #[cfg(not(feature = "zeroize"))]
+    {
+        for item in buf.iter_mut() {
+            // SAFETY: `item` is a valid, aligned, exclusive reference.
+            unsafe { core::ptr::write_volatile(item, T::default()) };
+        }
+        core::sync::atomic::compiler_fence(
+            core::sync::atomic::Ordering::SeqCst,
+        );
+    }
+}
+
+// Expected `self_test` shared secret for Kyber512
+#[cfg(all(
+    feature = "kyber512",
+    not(any(feature = "90s", feature = "turboshake"))
+))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x48, 0x4c, 0x65, 0xaa, 0x18, 0xa6, 0x95, 0x5f, 0x7a, 0x9f, 0x70,
+    0x13, 0x7c, 0x88, 0x2f, 0xcd, 0xbf, 0x0b, 0xd7, 0x32, 0xd1, 0x5c,
+    0xcf, 0x20, 0x4a, 0x25, 0x0b, 0xd1, 0x7b, 0xf3, 0xfc, 0x4f,
+];
+// Expected `self_test` shared secret for Kyber768
+#[cfg(all(
+    not(any(feature = "kyber512", feature = "kyber1024")),
+    not(any(feature = "90s", feature = "turboshake"))
+))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x79, 0x73, 0x13, 0x0d, 0xd7, 0x59, 0xb8, 0x54, 0x82, 0x4a, 0x18,
+    0xa0, 0xe0, 0x46, 0xaf, 0xd2, 0x6c, 0xdd, 0x02, 0xec, 0x87, 0x47,
+    0x34, 0x20, 0x0b, 0xc9, 0x8d, 0x38, 0x79, 0x65, 0xde, 0x7c,
+];
+// Expected `self_test` shared secret for Kyber1024
+#[cfg(all(
+    feature = "kyber1024",
+    not(any(feature = "90s", feature = "turboshake"))
+))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x66, 0xcd, 0x15, 0xc0, 0x9e, 0x37, 0x2f, 0xe6, 0x45, 0x22, 0xae,
+    0xa8, 0xc8, 0x08, 0x68, 0x44, 0x99, 0x9c, 0xe7, 0xf1, 0x65, 0x65,
+    0xb4, 0xa0, 0x43, 0x68, 0x0b, 0xf0, 0xbc, 0x95, 0x08, 0x3b,
+];
+// Expected `self_test` shared secret for Kyber512-90s
+#[cfg(all(feature = "kyber512", feature = "90s"))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0xa6, 0x6a, 0x40, 0xf4, 0x83, 0xdf, 0xd7, 0x5e, 0x23, 0x31, 0xd0,
+    0x10, 0x6c, 0xcc, 0x48, 0x77, 0xbc, 0xed, 0xe7, 0xcb, 0x42, 0x56,
+    0x0a, 0x9f, 0x4f, 0x83, 0x6b, 0x8c, 0xf4, 0xf6, 0x72, 0x2c,
+];
+// Expected `self_test` shared secret for Kyber768-90s
+#[cfg(all(
+    not(any(feature = "kyber512", feature = "kyber1024")),
+    feature = "90s"
+))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0xed, 0x5e, 0x28, 0xb6, 0x5b, 0x0b, 0xa5, 0xc4, 0xd1, 0x8f, 0x05,
+    0xf4, 0xdc, 0xe8, 0x88, 0xb9, 0x1f, 0x2f, 0xf3, 0x68, 0x4c, 0x8f,
+    0x4b, 0x2f, 0x09, 0x5e, 0x4e, 0xe6, 0xff, 0xeb, 0x8f, 0xcd,
+];
+// Expected `self_test` shared secret for Kyber1024-90s
+#[cfg(all(feature = "kyber1024", feature = "90s"))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x50, 0x7b, 0x61, 0x37, 0x37, 0x8f, 0xac, 0x01, 0x88, 0xcb, 0x04,
+    0xc3, 0xa7, 0xc9, 0x4f, 0x7d, 0xdf, 0xbc, 0x2b, 0x0c, 0xb5, 0x77,
+    0x09, 0x5f, 0x2e, 0x53, 0x8d, 0x07, 0x6e, 0xe6, 0x77, 0xd3,
+];
+// Expected `self_test` shared secret for Kyber512 with TurboSHAKE
+#[cfg(all(feature = "kyber512", feature = "turboshake"))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x6b, 0x68, 0xa2, 0x4d, 0x8d, 0x6d, 0x1c, 0x0f, 0x77, 0x56, 0xc6,
+    0x41, 0x02, 0x33, 0x47, 0x25, 0x40, 0xe9, 0xce, 0x0b, 0x75, 0x5d,
+    0x7d, 0xf8, 0xbc, 0x2c, 0xbc, 0x77, 0xdb, 0xfe, 0x88, 0xa1,
+];
+// Expected `self_test` shared secret for Kyber768 with TurboSHAKE
+#[cfg(all(
+    not(any(feature = "kyber512", feature = "kyber1024")),
+    feature = "turboshake"
+))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x80, 0xe4, 0x19, 0x0b, 0xbc, 0x7d, 0xdb, 0xa2, 0xee, 0x84, 0x71,
+    0xc0, 0x1c, 0x0a, 0x42, 0x4a, 0x25, 0x11, 0x64, 0x09, 0xbe, 0x9a,
+    0xcb, 0x16, 0xf1, 0xce, 0x2d, 0xbe, 0x08, 0x21, 0x4f, 0x8d,
+];
+// Expected `self_test` shared secret for Kyber1024 with TurboSHAKE
+#[cfg(all(feature = "kyber1024", feature = "turboshake"))]
+const SELF_TEST_SHARED_SECRET: [u8; KYBER_SHARED_SECRET_BYTES] = [
+    0x17, 0x09, 0xb4, 0xf3, 0x5d, 0x71, 0x61, 0xcc, 0x00, 0x4b, 0xcf,
+    0xc1, 0x80, 0x29, 0x94, 0x14, 0xe9, 0x29, 0x36, 0x51, 0x98, 0x7b,
+    0x8a, 0x49, 0xbd, 0xaa, 0x42, 0xca, 0x60, 0x0a, 0x3f, 0x49,
+];
+
+/// Runs a power-on self-test of the compiled Kyber implementation.
+///
+/// A keypair is derived from the fixed seed `00 01 02 .. 3f`, encapsulated
+/// to with the fixed coins `40 41 .. 5f` and decapsulated again. Both shared
+/// secrets must equal the expected value for the compiled security level
+/// and mode, which exercises the NTT, polynomial arithmetic and symmetric
+/// primitives in a single call:
+///
+/// | Level         | Expected shared secret (first 8 bytes) |
+/// |---------------|----------------------------------------|
+/// | Kyber512      | `484c65aa18a6955f…` |
+/// | Kyber768      | `7973130dd759b854…` |
+/// | Kyber1024     | `66cd15c09e372fe6…` |
+/// | Kyber512-90s  | `a66a40f483dfd75e…` |
+/// | Kyber768-90s  | `ed5e28b65b0ba5c4…` |
+/// | Kyber1024-90s | `507b6137378fac01…` |
+/// | Kyber512 with `turboshake`  | `6b68a24d8d6d1c0f…` |
+/// | Kyber768 with `turboshake`  | `80e4190bbc7ddba2…` |
+/// | Kyber1024 with `turboshake` | `1709b4f35d7161cc…` |
+///
+/// The standard and 90s values were produced by the Round 3 reference
+/// implementation. The `turboshake` values are specific to this crate,
+/// whose TurboSHAKE matches the RFC 9861 test vectors.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::SelfTestFailure` if either shared secret
+/// differs from the expected value.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// self_test()?;
+/// # Ok(()) }
+/// ```
+pub fn self_test() -> Result<(), KyberLibError> {
+    let mut seed = [0u8; 3 * KYBER_SYM_BYTES];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let keys = derive(&seed[..2 * KYBER_SYM_BYTES])?;
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    encrypt_message(
+        &mut ct,
+        &mut ss,
+        &keys.public,
+        &mut DummyRng {},
+        Some(&seed[2 * KYBER_SYM_BYTES..]),
+    )?;
+    let decapsulated = decapsulate(&ct, &keys.secret)?;
+    if ss != SELF_TEST_SHARED_SECRET
+        || decapsulated != SELF_TEST_SHARED_SECRET
+    {
+        return Err(KyberLibError::SelfTestFailure);
+    }
+    Ok(())
+}
+
 /// Extracts a public key from a private key.
 ///
 /// This function extracts the public key from a private key.
@@ -388,6 +388,25 @@ pub fn shake256(
     shake256_squeeze(&mut out[idx..], outlen, &mut state);
 }
 
+// SHAKE256 of the concatenation of `parts`, absorbed byte by byte so the
+// inputs need not be copied into one buffer
+pub fn shake256_concat(out: &mut [u8], outlen: usize, parts: &[&[u8]]) {
+    let mut state = KeccakState::new();
+    let mut pos = 0;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        state.s[pos / 8] ^= u64::from(byte) << (8 * (pos % 8));
+        pos += 1;
+        if pos == SHAKE256_RATE {
+            keccakf1600_statepermute(&mut state.s);
+            pos = 0;
+        }
+    }
+    state.s[pos / 8] ^= 0x1Fu64 << (8 * (pos % 8));
+    state.s[(SHAKE256_RATE - 1) / 8] ^= 1u64 << 63;
+    state.pos = SHAKE256_RATE;
+    shake256_squeeze(out, outlen, &mut state);
+}
+
 /// Name:  sha3_256
 ///
 /// Description: SHA3-256 with non-incremental API
@@ -606,7 +625,7 @@ fn shake256_squeezeblocks(
     keccak_squeezeblocks(out, nblocks, &mut state.s, SHAKE256_RATE);
 }
 
-fn shake128(
+pub fn shake128(
     out: &mut [u8],
     mut outlen: usize,
     input: &[u8],
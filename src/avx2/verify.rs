@@ -1,6 +1,10 @@
 use core::arch::x86_64::*;
 
 pub fn verify(a: &[u8], b: &[u8], mut len: usize) -> u8 {
+    debug_assert!(
+        a.len() == len && b.len() == len,
+        "verify: input length mismatch"
+    );
     let (mut f, mut g);
     let mut r: u64;
     unsafe {
@@ -0,0 +1,205 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! C ABI for calling KyberLib from C and C++.
+//!
+//! Buffers are passed as a pointer and a length, and every function
+//! returns `KYBERLIB_OK` or one of the negative `KYBERLIB_ERR_*` status
+//! codes. The lengths must equal the sizes reported by the
+//! `kyberlib_*_bytes` functions for the compiled security level. Output
+//! buffers are only written on success.
+//!
+//! Generate the header with
+//! `cbindgen --config cbindgen.toml --crate kyberlib --output kyberlib.h`.
+
+use crate::{api, params::*, KyberLibError};
+use core::{ffi::c_int, slice};
+use rand::rngs::OsRng;
+
+/// The call succeeded.
+pub const KYBERLIB_OK: c_int = 0;
+/// A pointer was null or a buffer had the wrong length.
+pub const KYBERLIB_ERR_INVALID_INPUT: c_int = -1;
+/// Key generation failed or the key was rejected.
+pub const KYBERLIB_ERR_INVALID_KEY: c_int = -2;
+/// A buffer had an invalid length.
+pub const KYBERLIB_ERR_INVALID_LENGTH: c_int = -3;
+/// The ciphertext could not be decapsulated.
+pub const KYBERLIB_ERR_DECAPSULATION: c_int = -4;
+/// The system random number generator failed.
+pub const KYBERLIB_ERR_RANDOM_BYTES: c_int = -5;
+/// A key exchange step was called out of order.
+pub const KYBERLIB_ERR_HANDSHAKE_ORDER: c_int = -6;
+/// The power-on self-test failed.
+pub const KYBERLIB_ERR_SELF_TEST: c_int = -7;
+/// A key exchange was started while another was in progress.
+pub const KYBERLIB_ERR_HANDSHAKE_IN_PROGRESS: c_int = -8;
+
+// Maps a library error to its C status code
+fn status(err: KyberLibError) -> c_int {
+    match err {
+        KyberLibError::InvalidInput => KYBERLIB_ERR_INVALID_INPUT,
+        KyberLibError::InvalidKey => KYBERLIB_ERR_INVALID_KEY,
+        KyberLibError::InvalidLength => KYBERLIB_ERR_INVALID_LENGTH,
+        KyberLibError::Decapsulation => KYBERLIB_ERR_DECAPSULATION,
+        KyberLibError::RandomBytesGeneration
+        | KyberLibError::RandomBytesGenerationSized { .. } => {
+            KYBERLIB_ERR_RANDOM_BYTES
+        }
+        KyberLibError::HandshakeOutOfOrder => {
+            KYBERLIB_ERR_HANDSHAKE_ORDER
+        }
+        KyberLibError::SelfTestFailure => KYBERLIB_ERR_SELF_TEST,
+        KyberLibError::HandshakeInProgress => {
+            KYBERLIB_ERR_HANDSHAKE_IN_PROGRESS
+        }
+    }
+}
+
+// Borrows a caller buffer, checking the pointer and length
+unsafe fn input<'a>(
+    ptr: *const u8,
+    len: usize,
+    expected: usize,
+) -> Result<&'a [u8], c_int> {
+    if ptr.is_null() || len != expected {
+        return Err(KYBERLIB_ERR_INVALID_INPUT);
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn output<'a>(
+    ptr: *mut u8,
+    len: usize,
+    expected: usize,
+) -> Result<&'a mut [u8], c_int> {
+    if ptr.is_null() || len != expected {
+        return Err(KYBERLIB_ERR_INVALID_INPUT);
+    }
+    Ok(slice::from_raw_parts_mut(ptr, len))
+}
+
+/// Returns the public key size of the compiled security level.
+#[no_mangle]
+pub extern "C" fn kyberlib_public_key_bytes() -> usize {
+    KYBER_PUBLIC_KEY_BYTES
+}
+
+/// Returns the secret key size of the compiled security level.
+#[no_mangle]
+pub extern "C" fn kyberlib_secret_key_bytes() -> usize {
+    KYBER_SECRET_KEY_BYTES
+}
+
+/// Returns the ciphertext size of the compiled security level.
+#[no_mangle]
+pub extern "C" fn kyberlib_ciphertext_bytes() -> usize {
+    KYBER_CIPHERTEXT_BYTES
+}
+
+/// Returns the shared secret size.
+#[no_mangle]
+pub extern "C" fn kyberlib_shared_secret_bytes() -> usize {
+    KYBER_SHARED_SECRET_BYTES
+}
+
+/// Generates a keypair with the operating system RNG.
+///
+/// # Safety
+///
+/// `pk` and `sk` must be null or valid for writes of `pk_len` and
+/// `sk_len` bytes, and must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn kyberlib_keypair(
+    pk: *mut u8,
+    pk_len: usize,
+    sk: *mut u8,
+    sk_len: usize,
+) -> c_int {
+    let (pk, sk) = match (
+        output(pk, pk_len, KYBER_PUBLIC_KEY_BYTES),
+        output(sk, sk_len, KYBER_SECRET_KEY_BYTES),
+    ) {
+        (Ok(pk), Ok(sk)) => (pk, sk),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    match api::keypair(&mut OsRng) {
+        Ok(keys) => {
+            pk.copy_from_slice(&keys.public);
+            sk.copy_from_slice(&keys.secret);
+            KYBERLIB_OK
+        }
+        Err(err) => status(err),
+    }
+}
+
+/// Encapsulates a fresh shared secret to the public key `pk`.
+///
+/// # Safety
+///
+/// `pk` must be null or valid for reads of `pk_len` bytes, and `ct` and
+/// `ss` must be null or valid for writes of `ct_len` and `ss_len`
+/// bytes. The buffers must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn kyberlib_encapsulate(
+    pk: *const u8,
+    pk_len: usize,
+    ct: *mut u8,
+    ct_len: usize,
+    ss: *mut u8,
+    ss_len: usize,
+) -> c_int {
+    let (pk, ct, ss) = match (
+        input(pk, pk_len, KYBER_PUBLIC_KEY_BYTES),
+        output(ct, ct_len, KYBER_CIPHERTEXT_BYTES),
+        output(ss, ss_len, KYBER_SHARED_SECRET_BYTES),
+    ) {
+        (Ok(pk), Ok(ct), Ok(ss)) => (pk, ct, ss),
+        (Err(code), _, _) | (_, Err(code), _) | (_, _, Err(code)) => {
+            return code
+        }
+    };
+    match api::encapsulate(pk, &mut OsRng) {
+        Ok((ciphertext, shared_secret)) => {
+            ct.copy_from_slice(&ciphertext);
+            ss.copy_from_slice(&shared_secret);
+            KYBERLIB_OK
+        }
+        Err(err) => status(err),
+    }
+}
+
+/// Decapsulates the ciphertext `ct` with the secret key `sk`.
+///
+/// # Safety
+///
+/// `ct` and `sk` must be null or valid for reads of `ct_len` and
+/// `sk_len` bytes, and `ss` must be null or valid for writes of
+/// `ss_len` bytes. The buffers must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn kyberlib_decapsulate(
+    ct: *const u8,
+    ct_len: usize,
+    sk: *const u8,
+    sk_len: usize,
+    ss: *mut u8,
+    ss_len: usize,
+) -> c_int {
+    let (ct, sk, ss) = match (
+        input(ct, ct_len, KYBER_CIPHERTEXT_BYTES),
+        input(sk, sk_len, KYBER_SECRET_KEY_BYTES),
+        output(ss, ss_len, KYBER_SHARED_SECRET_BYTES),
+    ) {
+        (Ok(ct), Ok(sk), Ok(ss)) => (ct, sk, ss),
+        (Err(code), _, _) | (_, Err(code), _) | (_, _, Err(code)) => {
+            return code
+        }
+    };
+    match api::decapsulate(ct, sk) {
+        Ok(shared_secret) => {
+            ss.copy_from_slice(&shared_secret);
+            KYBERLIB_OK
+        }
+        Err(err) => status(err),
+    }
+}
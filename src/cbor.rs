@@ -0,0 +1,187 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! CBOR encodings of keypairs and handshake messages.
+//!
+//! Each value is a CBOR map with small integer keys rather than field
+//! names, so the encoding is only a few bytes longer than the raw
+//! key or message:
+//!
+//! - a `Keypair` is `{1: public key, 2: secret key}`;
+//! - a `HandshakeMessage` is `{1: frame kind, 2: payload}`, where the
+//!   kind is one of the `FRAME_*` tags from the `frame` module.
+//!
+//! Keys and payloads are CBOR byte strings, and decoding checks their
+//! lengths against the compiled security level.
+
+use crate::{
+    api::wipe, frame::frame_message, kex::HandshakeMessage, params::*,
+    Keypair, KyberLibError,
+};
+use ciborium::value::{Integer, Value};
+use core::convert::TryFrom;
+
+/// Map key of a keypair's public key.
+pub const CBOR_KEY_PUBLIC: u8 = 1;
+/// Map key of a keypair's secret key.
+pub const CBOR_KEY_SECRET: u8 = 2;
+/// Map key of a handshake message's frame kind.
+pub const CBOR_KEY_KIND: u8 = 1;
+/// Map key of a handshake message's payload.
+pub const CBOR_KEY_PAYLOAD: u8 = 2;
+
+/// Encodes a keypair as a CBOR map.
+///
+/// The secret key is included, so treat the output as secret. The
+/// intermediate copy of the secret key is wiped before returning.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// use kyberlib::cbor::{keypair_from_cbor, keypair_to_cbor};
+/// # fn main() -> Result<(), KyberLibError> {
+/// let keys = keypair(&mut rand::thread_rng())?;
+/// let bytes = keypair_to_cbor(&keys);
+/// assert_eq!(keypair_from_cbor(&bytes)?, keys);
+/// # Ok(())}
+/// ```
+pub fn keypair_to_cbor(keys: &Keypair) -> Vec<u8> {
+    let mut map = Value::Map(vec![
+        (CBOR_KEY_PUBLIC.into(), Value::Bytes(keys.public.to_vec())),
+        (CBOR_KEY_SECRET.into(), Value::Bytes(keys.secret.to_vec())),
+    ]);
+    let out = encode(&map);
+    wipe_map(&mut map);
+    out
+}
+
+/// Decodes a keypair produced by `keypair_to_cbor`.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if the bytes are not a CBOR
+/// map with exactly the expected keys, and
+/// `KyberLibError::InvalidLength` if a key has the wrong size.
+pub fn keypair_from_cbor(
+    bytes: &[u8],
+) -> Result<Keypair, KyberLibError> {
+    let [public, secret] =
+        decode(bytes, [CBOR_KEY_PUBLIC, CBOR_KEY_SECRET])?;
+    let mut secret = as_bytes(secret)?;
+    let result = as_bytes(public).and_then(|public| {
+        if public.len() != KYBER_PUBLIC_KEY_BYTES
+            || secret.len() != KYBER_SECRET_KEY_BYTES
+        {
+            return Err(KyberLibError::InvalidLength);
+        }
+        let mut keys = Keypair {
+            public: [0u8; KYBER_PUBLIC_KEY_BYTES],
+            secret: [0u8; KYBER_SECRET_KEY_BYTES],
+        };
+        keys.public.copy_from_slice(&public);
+        keys.secret.copy_from_slice(&secret);
+        Ok(keys)
+    });
+    wipe(&mut secret);
+    result
+}
+
+/// Encodes a handshake message as a CBOR map.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// use kyberlib::cbor::{message_from_cbor, message_to_cbor};
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let bob_keys = keypair(&mut rng)?;
+/// let mut alice = Uake::new();
+/// let init = alice.client_init(&bob_keys.public, &mut rng)?;
+/// let bytes = message_to_cbor(&HandshakeMessage::UakeInit(init));
+/// assert_eq!(message_from_cbor(&bytes)?, HandshakeMessage::UakeInit(init));
+/// # Ok(())}
+/// ```
+pub fn message_to_cbor(msg: &HandshakeMessage) -> Vec<u8> {
+    encode(&Value::Map(vec![
+        (CBOR_KEY_KIND.into(), msg.kind().into()),
+        (
+            CBOR_KEY_PAYLOAD.into(),
+            Value::Bytes(msg.payload().to_vec()),
+        ),
+    ]))
+}
+
+/// Decodes a handshake message produced by `message_to_cbor`.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidInput` if the bytes are not a CBOR
+/// map with exactly the expected keys or the kind is unknown, and
+/// `KyberLibError::InvalidLength` if the payload does not match the
+/// size of the tagged variant.
+pub fn message_from_cbor(
+    bytes: &[u8],
+) -> Result<HandshakeMessage, KyberLibError> {
+    let [kind, payload] =
+        decode(bytes, [CBOR_KEY_KIND, CBOR_KEY_PAYLOAD])?;
+    let kind = kind
+        .as_integer()
+        .and_then(|kind| u8::try_from(kind).ok())
+        .ok_or(KyberLibError::InvalidInput)?;
+    let payload = as_bytes(payload)?;
+    HandshakeMessage::from_bytes(&frame_message(kind, &payload))
+}
+
+// Serializes a map in the order given
+fn encode(map: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(map, &mut out)
+        .expect("writing CBOR to a Vec cannot fail");
+    out
+}
+
+// Wipes the byte strings of a map that held secret key material
+fn wipe_map(map: &mut Value) {
+    if let Value::Map(entries) = map {
+        for (_, value) in entries.iter_mut() {
+            if let Value::Bytes(bytes) = value {
+                wipe(bytes);
+            }
+        }
+    }
+}
+
+// Parses a map holding exactly the given keys, returning their values
+// in the same order
+fn decode<const N: usize>(
+    bytes: &[u8],
+    keys: [u8; N],
+) -> Result<[Value; N], KyberLibError> {
+    let value: Value = ciborium::de::from_reader(bytes)
+        .map_err(|_| KyberLibError::InvalidInput)?;
+    let entries = match value {
+        Value::Map(entries) if entries.len() == N => entries,
+        _ => return Err(KyberLibError::InvalidInput),
+    };
+    let mut values: [Option<Value>; N] = core::array::from_fn(|_| None);
+    for (key, value) in entries {
+        let index = key
+            .as_integer()
+            .and_then(|key| {
+                keys.iter().position(|&k| Integer::from(k) == key)
+            })
+            .ok_or(KyberLibError::InvalidInput)?;
+        if values[index].replace(value).is_some() {
+            return Err(KyberLibError::InvalidInput);
+        }
+    }
+    let mut out: [Value; N] = core::array::from_fn(|_| Value::Null);
+    for (slot, value) in out.iter_mut().zip(values) {
+        *slot = value.ok_or(KyberLibError::InvalidInput)?;
+    }
+    Ok(out)
+}
+
+fn as_bytes(value: Value) -> Result<Vec<u8>, KyberLibError> {
+    value.into_bytes().map_err(|_| KyberLibError::InvalidInput)
+}
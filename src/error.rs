@@ -19,6 +19,24 @@ pub enum KyberLibError {
 
     /// Error trying to fill random bytes (i.e., external (hardware) RNG modules can fail).
     RandomBytesGeneration,
+
+    /// The RNG failed while filling `requested` random bytes. Returned by `randombytes`,
+    /// so logs show which draw failed.
+    RandomBytesGenerationSized {
+        /// Number of bytes the RNG was asked for.
+        requested: usize,
+    },
+
+    /// A key exchange step was called out of order, e.g. `client_confirm()` before
+    /// `client_init()`.
+    HandshakeOutOfOrder,
+
+    /// `client_init()` was called again before the exchange it started was confirmed,
+    /// which would overwrite its ephemeral key.
+    HandshakeInProgress,
+
+    /// The power-on self-test produced an unexpected shared secret.
+    SelfTestFailure,
 }
 
 impl core::fmt::Display for KyberLibError {
@@ -32,12 +50,24 @@ impl core::fmt::Display for KyberLibError {
             KyberLibError::RandomBytesGeneration => {
                 write!(f, "Random bytes generation function failed")
             }
+            KyberLibError::RandomBytesGenerationSized { requested } => {
+                write!(f, "Random bytes generation function failed for {} bytes", requested)
+            }
             KyberLibError::InvalidKey => {
                 write!(f, "The secret and public key given does not match.")
             },
             KyberLibError::InvalidLength => {
                 write!(f, "The length of the input buffer is invalid.")
             }
+            KyberLibError::HandshakeOutOfOrder => {
+                write!(f, "Key exchange step called out of order.")
+            }
+            KyberLibError::HandshakeInProgress => {
+                write!(f, "A key exchange is already in progress.")
+            }
+            KyberLibError::SelfTestFailure => {
+                write!(f, "Self-test produced an unexpected shared secret.")
+            }
         }
     }
 }
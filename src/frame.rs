@@ -0,0 +1,77 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Length-prefixed framing for sending handshake messages over a byte
+//! stream.
+//!
+//! Each frame is a one-byte kind tag, the payload length as a 4-byte
+//! big-endian integer, then the payload. The tag tells the receiver
+//! which handshake message it holds, and the length lets it split a
+//! stream that carries several frames back to back.
+
+extern crate alloc;
+
+use crate::KyberLibError;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+/// Frame kind of a `Uake` client initiation.
+pub const FRAME_UAKE_INIT: u8 = 1;
+/// Frame kind of a `Uake` server response.
+pub const FRAME_UAKE_RESPONSE: u8 = 2;
+/// Frame kind of an `Ake` client initiation.
+pub const FRAME_AKE_INIT: u8 = 3;
+/// Frame kind of an `Ake` server response.
+pub const FRAME_AKE_RESPONSE: u8 = 4;
+
+/// Length of the kind tag and length prefix preceding each payload.
+pub const FRAME_HEADER_BYTES: usize = 5;
+
+/// Tags and length-prefixes a payload.
+///
+/// # Panics
+///
+/// Panics if the payload is longer than `u32::MAX` bytes.
+///
+/// # Example
+/// ```
+/// use kyberlib::frame::{frame_message, parse_frame, FRAME_AKE_INIT};
+/// let frame = frame_message(FRAME_AKE_INIT, b"hello");
+/// assert_eq!(frame.len(), 5 + 5);
+/// assert_eq!(parse_frame(&frame), Ok((FRAME_AKE_INIT, &b"hello"[..], &[][..])));
+/// ```
+pub fn frame_message(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let len =
+        u32::try_from(payload.len()).expect("frame payload too long");
+    let mut frame =
+        Vec::with_capacity(FRAME_HEADER_BYTES + payload.len());
+    frame.push(kind);
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Splits the first frame off a buffer.
+///
+/// Returns the frame kind, its payload and the bytes following it.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidLength` if the buffer is shorter than
+/// the header or than the length it announces.
+pub fn parse_frame(
+    buf: &[u8],
+) -> Result<(u8, &[u8], &[u8]), KyberLibError> {
+    if buf.len() < FRAME_HEADER_BYTES {
+        return Err(KyberLibError::InvalidLength);
+    }
+    let mut len = [0u8; 4];
+    len.copy_from_slice(&buf[1..FRAME_HEADER_BYTES]);
+    let len = u32::from_be_bytes(len) as usize;
+    let body = &buf[FRAME_HEADER_BYTES..];
+    if body.len() < len {
+        return Err(KyberLibError::InvalidLength);
+    }
+    let (payload, rest) = body.split_at(len);
+    Ok((buf[0], payload, rest))
+}
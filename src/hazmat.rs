@@ -0,0 +1,261 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Low-level operations that bypass the KEM's safety properties.
+//!
+//! With `debug-internals`, exposes internal representations, only
+//! meant for diffing this implementation's decoding against another's
+//! when chasing interop mismatches. With `hazmat`, exposes the raw
+//! IND-CPA encryption and decryption for teaching, research and
+//! commitment schemes, and the constant-time select behind implicit
+//! rejection.
+
+#[cfg(feature = "debug-internals")]
+extern crate alloc;
+
+#[cfg(feature = "hazmat")]
+use crate::verify::cmov;
+#[cfg(feature = "debug-internals")]
+use crate::{
+    api::{derive, Keypair},
+    poly::Poly,
+    polyvec::{polyvec_frombytes, polyvec_tobytes, Polyvec},
+    KyberLibError,
+};
+use crate::{kex::*, params::*};
+#[cfg(feature = "debug-internals")]
+use alloc::vec::Vec;
+#[cfg(feature = "debug-internals")]
+use core::cell::Cell;
+
+#[cfg(feature = "debug-internals")]
+std::thread_local! {
+    // Rejection-sampling iterations of the last matrix expansion on
+    // this thread
+    pub(crate) static REJECTION_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns how many rejection-sampling iterations the most recent
+/// expansion of the matrix `A` on this thread performed.
+///
+/// Each iteration reads 3 bytes of SHAKE128 output and tests the two
+/// 12-bit candidates in them against `q`, so the count is at least
+/// `KYBER_SECURITY_PARAMETER^2 * KYBER_N / 2` and grows with the number
+/// of rejected candidates. Key generation expands the matrix once, and
+/// the count is reset at the start of every expansion, so it reflects
+/// only the last call. This is meant for quantifying the variable-time
+/// behaviour of the sampler, which only depends on the public seed.
+/// Only the reference implementation is instrumented: in AVX2 builds
+/// the count stays 0.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let _keys = derive(&[7u8; 64])?;
+/// assert!(hazmat::last_rejection_count() > 0);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "debug-internals")]
+pub fn last_rejection_count() -> usize {
+    REJECTION_COUNT.with(Cell::get)
+}
+
+/// Decodes the polynomial vector of a public key.
+///
+/// Returns the `KYBER_SECURITY_PARAMETER * KYBER_N` coefficients in
+/// order, polynomial by polynomial, exactly as unpacked from the
+/// little-endian 12-bit encoding (still in the NTT domain).
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let coeffs = hazmat::decode_public_poly(&keys.public);
+/// assert_eq!(coeffs.len(), KYBER_SECURITY_PARAMETER * params::KYBER_N);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "debug-internals")]
+pub fn decode_public_poly(pk: &PublicKey) -> Vec<i16> {
+    let mut pkpv = Polyvec::new();
+    polyvec_frombytes(&mut pkpv, &pk[..KYBER_POLYVEC_BYTES]);
+    pkpv.vec.iter().flat_map(|poly| poly.coeffs).collect()
+}
+
+/// Encodes coefficients from [`decode_public_poly`] and a matrix seed
+/// back into a public key.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidLength` if `coeffs` does not hold
+/// `KYBER_SECURITY_PARAMETER * KYBER_N` coefficients.
+#[cfg(feature = "debug-internals")]
+pub fn encode_public_poly(
+    coeffs: &[i16],
+    seed: &[u8; KYBER_SYM_BYTES],
+) -> Result<PublicKey, KyberLibError> {
+    if coeffs.len() != KYBER_SECURITY_PARAMETER * KYBER_N {
+        return Err(KyberLibError::InvalidLength);
+    }
+    let mut pkpv = Polyvec::new();
+    for (poly, chunk) in pkpv.vec.iter_mut().zip(coeffs.chunks(KYBER_N))
+    {
+        *poly = Poly::new();
+        poly.coeffs.copy_from_slice(chunk);
+    }
+    let mut pk = [0u8; KYBER_PUBLIC_KEY_BYTES];
+    polyvec_tobytes(&mut pk, &pkpv);
+    pk[KYBER_POLYVEC_BYTES..].copy_from_slice(seed);
+    Ok(pk)
+}
+
+/// Derives a keypair like [`derive`](crate::derive) and also returns
+/// the `d` and `z` halves of the seed it used.
+///
+/// `d` is the seed expanded into the matrix seed and the secret noise,
+/// and `z` is the implicit-rejection value stored at the end of the
+/// secret key. `z` is read back from the derived secret key, so an
+/// auditor sees the value that actually ended up in the key.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// let seed = [3u8; 64];
+/// let (keys, d, z) = hazmat::derive_with_intermediates(&seed);
+/// assert_eq!((d, z), ([3u8; 32], [3u8; 32]));
+/// assert_eq!(keys, derive(&seed).unwrap());
+/// ```
+#[cfg(feature = "debug-internals")]
+pub fn derive_with_intermediates(
+    seed: &[u8; 2 * KYBER_SYM_BYTES],
+) -> (Keypair, [u8; KYBER_SYM_BYTES], [u8; KYBER_SYM_BYTES]) {
+    let keys = derive(seed).expect("64-byte seed");
+    let mut d = [0u8; KYBER_SYM_BYTES];
+    let mut z = [0u8; KYBER_SYM_BYTES];
+    d.copy_from_slice(&seed[..KYBER_SYM_BYTES]);
+    z.copy_from_slice(
+        &keys.secret[KYBER_SECRET_KEY_BYTES - KYBER_SYM_BYTES..],
+    );
+    (keys, d, z)
+}
+
+/// Decrypts a ciphertext with IND-CPA decryption only, returning the
+/// recovered message.
+///
+/// # Warning
+///
+/// This is **not** IND-CCA secure. It skips the Fujisaki-Okamoto
+/// re-encryption check and implicit rejection that `decapsulate`
+/// performs, so it returns whatever message a malformed or tampered
+/// ciphertext decrypts to. Exposing its output, or any timing or error
+/// behaviour that depends on it, to an attacker who can submit
+/// ciphertexts leaks the secret key. Use it for teaching and research
+/// only, never to derive session keys.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, _ss) = encapsulate(&keys.public, &mut rng)?;
+/// let m = hazmat::decapsulate_cpa(&ct, &keys.secret);
+/// assert_eq!(m.len(), KYBER_SYM_BYTES);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "hazmat")]
+pub fn decapsulate_cpa(
+    ct: &[u8; KYBER_CIPHERTEXT_BYTES],
+    sk: &SecretKey,
+) -> [u8; KYBER_SYM_BYTES] {
+    let mut m = [0u8; KYBER_SYM_BYTES];
+    crate::indcpa::indcpa_dec(&mut m, ct, sk);
+    m
+}
+
+/// Encrypts a chosen message under a public key with IND-CPA
+/// encryption, deterministically from `coins`.
+///
+/// The same `(pk, msg, coins)` always gives the same ciphertext, which
+/// suits commitment schemes: publish the ciphertext, later reveal
+/// `msg` and `coins` and let the verifier re-encrypt. [`cpa_decrypt`]
+/// recovers `msg` with the matching secret key.
+///
+/// # Warning
+///
+/// This is **IND-CPA only**: no Fujisaki-Okamoto transform is applied,
+/// so reusing `coins` for two messages, or choosing them non-uniformly,
+/// leaks information about the messages. Draw fresh random coins per
+/// encryption unless you are deliberately re-encrypting to verify an
+/// opening.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let msg = [42u8; 32];
+/// let coins = [7u8; 32];
+/// let ct = hazmat::cpa_encrypt(&keys.public, &msg, &coins);
+/// assert_eq!(ct, hazmat::cpa_encrypt(&keys.public, &msg, &coins));
+/// assert_eq!(hazmat::cpa_decrypt(&keys.secret, &ct), msg);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "hazmat")]
+pub fn cpa_encrypt(
+    pk: &PublicKey,
+    msg: &[u8; KYBER_SYM_BYTES],
+    coins: &[u8; KYBER_SYM_BYTES],
+) -> [u8; KYBER_CIPHERTEXT_BYTES] {
+    let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+    crate::indcpa::indcpa_enc(&mut ct, msg, pk, coins);
+    ct
+}
+
+/// Decrypts a ciphertext made by [`cpa_encrypt`] with IND-CPA
+/// decryption.
+///
+/// The same operation as [`decapsulate_cpa`], with the arguments in
+/// `cpa_encrypt` order. The warnings there apply: a tampered ciphertext
+/// decrypts to an attacker-influenced message without any error.
+#[cfg(feature = "hazmat")]
+pub fn cpa_decrypt(
+    sk: &SecretKey,
+    ct: &[u8; KYBER_CIPHERTEXT_BYTES],
+) -> [u8; KYBER_SYM_BYTES] {
+    decapsulate_cpa(ct, sk)
+}
+
+/// Copies `src` into `dst` if `condition` is nonzero, in constant time.
+///
+/// This is the conditional move used by decapsulation to substitute the
+/// implicit-rejection key, exposed for protocols built on top of Kyber
+/// (oblivious transfer, PAKE) that must select between secrets without
+/// branching. Every byte of `dst` is read and written whatever the
+/// condition, and `condition` is reduced to a single bit without a
+/// data-dependent branch.
+///
+/// # Panics
+///
+/// Panics if `dst` and `src` have different lengths.
+///
+/// # Example
+/// ```
+/// # use kyberlib::hazmat::ct_select;
+/// let mut dst = [1u8; 4];
+/// ct_select(&mut dst, &[2u8; 4], 0);
+/// assert_eq!(dst, [1u8; 4]);
+/// ct_select(&mut dst, &[2u8; 4], 0xFF);
+/// assert_eq!(dst, [2u8; 4]);
+/// ```
+#[cfg(feature = "hazmat")]
+pub fn ct_select(dst: &mut [u8], src: &[u8], condition: u8) {
+    assert_eq!(dst.len(), src.len(), "ct_select length mismatch");
+    // 1 if condition is nonzero, 0 otherwise
+    let bit = (u32::from(condition).wrapping_neg() >> 31) as u8;
+    let len = dst.len();
+    cmov(dst, src, len, bit);
+}
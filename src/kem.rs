@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::{
-    error::KyberLibError, indcpa::*, params::*, rng::randombytes,
-    symmetric::*, verify::*,
+    error::KyberLibError, indcpa::*, params::*, polyvec::Polyvec,
+    rng::randombytes, symmetric::*, verify::*,
 };
 use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroizing;
 
 /// Generates a public and private key pair for CCA-secure Kyber key encapsulation mechanism.
 ///
@@ -14,11 +16,14 @@ use rand_core::{CryptoRng, RngCore};
 /// * `pk` - Output public key (an already allocated array of CRYPTO_PUBLICKEYBYTES bytes).
 /// * `sk` - Output private key (an already allocated array of CRYPTO_SECRETKEYBYTES bytes).
 /// * `_rng` - Random number generator implementing RngCore + CryptoRng.
-/// * `_seed` - Optional seed for key generation.
+/// * `_seed` - Optional seed for key generation: the key generation
+///   seed `d` and the implicit rejection value `z`, each
+///   `KYBER_SYM_BYTES` long.
 ///
 /// # Errors
 ///
-/// Returns a `KyberLibError` on failure.
+/// Returns `KyberLibError::InvalidInput` if either seed has the wrong
+/// length, and otherwise the error from the RNG.
 pub fn generate_key_pair<R>(
     pk: &mut [u8],
     sk: &mut [u8],
@@ -34,11 +39,17 @@ where
     const END: usize =
         KYBER_INDCPA_PUBLIC_KEY_BYTES + KYBER_INDCPA_SECRET_KEY_BYTES;
 
+    if let Some((d, z)) = _seed {
+        if d.len() != KYBER_SYM_BYTES || z.len() != KYBER_SYM_BYTES {
+            return Err(KyberLibError::InvalidInput);
+        }
+    }
+
     indcpa_keypair(pk, sk, _seed, _rng)?;
 
     sk[KYBER_INDCPA_SECRET_KEY_BYTES..END]
         .copy_from_slice(&pk[..KYBER_INDCPA_PUBLIC_KEY_BYTES]);
-    hash_h(&mut sk[PK_START..], pk, KYBER_PUBLIC_KEY_BYTES);
+    hash_h_unchecked(&mut sk[PK_START..], pk, KYBER_PUBLIC_KEY_BYTES);
 
     if let Some(s) = _seed {
         sk[SK_START..].copy_from_slice(s.1);
@@ -59,6 +70,13 @@ where
 /// * `_rng` - Random number generator implementing RngCore + CryptoRng.
 /// * `_seed` - Optional seed for random number generation.
 ///
+/// # Security
+///
+/// With the `zeroize` feature the random coins (`randbuf`), the hashed
+/// message (`buf`) and the pre-key and coins (`kr`) are wiped before
+/// this function returns, so they cannot be recovered from a later
+/// stack dump. The wipe happens on every return path, including errors.
+///
 /// # Errors
 ///
 /// Returns a `KyberLibError` on failure.
@@ -72,34 +90,95 @@ pub fn encrypt_message<R>(
 where
     R: RngCore + CryptoRng,
 {
-    let mut kr = [0u8; 2 * KYBER_SYM_BYTES];
-    let mut buf = [0u8; 2 * KYBER_SYM_BYTES];
-    let mut randbuf = [0u8; 2 * KYBER_SYM_BYTES];
+    encrypt_message_with_context(ct, ss, pk, _rng, _seed, None)
+}
+
+// Encapsulation, optionally binding the coins to an application context
+pub(crate) fn encrypt_message_with_context<R>(
+    ct: &mut [u8],
+    ss: &mut [u8],
+    pk: &[u8],
+    _rng: &mut R,
+    _seed: Option<&[u8]>,
+    context: Option<&[u8]>,
+) -> Result<(), KyberLibError>
+where
+    R: RngCore + CryptoRng,
+{
+    encrypt_message_with_ct_hash(ct, ss, pk, _rng, _seed, context)
+        .map(|_| ())
+}
+
+// Encapsulation that also returns H(c), the ciphertext hash mixed into
+// the shared secret
+pub(crate) fn encrypt_message_with_ct_hash<R>(
+    ct: &mut [u8],
+    ss: &mut [u8],
+    pk: &[u8],
+    _rng: &mut R,
+    _seed: Option<&[u8]>,
+    context: Option<&[u8]>,
+) -> Result<[u8; KYBER_SYM_BYTES], KyberLibError>
+where
+    R: RngCore + CryptoRng,
+{
+    let mut at = [Polyvec::new(); KYBER_SECURITY_PARAMETER];
+    indcpa_expand_at(&mut at, pk);
+    encrypt_message_prepared(ct, ss, pk, &at, _rng, _seed, context)
+}
+
+// Encapsulation with the matrix A of `pk` already expanded. Returns H(c).
+pub(crate) fn encrypt_message_prepared<R>(
+    ct: &mut [u8],
+    ss: &mut [u8],
+    pk: &[u8],
+    at: &PublicMatrix,
+    _rng: &mut R,
+    _seed: Option<&[u8]>,
+    context: Option<&[u8]>,
+) -> Result<[u8; KYBER_SYM_BYTES], KyberLibError>
+where
+    R: RngCore + CryptoRng,
+{
+    let mut kr = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    let mut buf = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    let mut randbuf = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
 
     // Deterministic randbuf for Known Answer Tests (KATs)
     if let Some(s) = _seed {
         randbuf[..KYBER_SYM_BYTES].copy_from_slice(s);
     } else {
-        randombytes(&mut randbuf, KYBER_SYM_BYTES, _rng)?;
+        randombytes(&mut randbuf[..], KYBER_SYM_BYTES, _rng)?;
     }
 
     // Don't release system RNG output
-    hash_h(&mut buf, &randbuf, KYBER_SYM_BYTES);
+    hash_h_unchecked(&mut buf[..], &randbuf[..], KYBER_SYM_BYTES);
 
     // Multitarget countermeasure for coins + contributory KEM
-    hash_h(&mut buf[KYBER_SYM_BYTES..], pk, KYBER_PUBLIC_KEY_BYTES);
-    hash_g(&mut kr, &buf, 2 * KYBER_SYM_BYTES);
+    hash_h_unchecked(
+        &mut buf[KYBER_SYM_BYTES..],
+        pk,
+        KYBER_PUBLIC_KEY_BYTES,
+    );
+    hash_coins(&mut kr[..], &buf[..], context);
 
     // Coins are in kr[KYBER_SYM_BYTES..]
-    indcpa_enc(ct, &buf, pk, &kr[KYBER_SYM_BYTES..]);
+    indcpa_enc_prepared(ct, &buf[..], pk, at, &kr[KYBER_SYM_BYTES..]);
 
     // Overwrite coins in kr with H(c)
-    hash_h(&mut kr[KYBER_SYM_BYTES..], ct, KYBER_CIPHERTEXT_BYTES);
+    hash_h_unchecked(
+        &mut kr[KYBER_SYM_BYTES..],
+        ct,
+        KYBER_CIPHERTEXT_BYTES,
+    );
 
     // Hash concatenation of pre-k and H(c) to derive the shared secret
-    kdf(ss, &kr, 2 * KYBER_SYM_BYTES);
+    kdf(ss, &kr[..], 2 * KYBER_SYM_BYTES);
 
-    Ok(())
+    let mut ct_hash = [0u8; KYBER_SYM_BYTES];
+    ct_hash.copy_from_slice(&kr[KYBER_SYM_BYTES..]);
+
+    Ok(ct_hash)
 }
 
 /// Generates a shared secret for a given cipher text and private key.
@@ -112,34 +191,133 @@ where
 ///
 /// On failure, `ss` will contain a pseudo-random value.
 pub fn decrypt_message(ss: &mut [u8], ct: &[u8], sk: &[u8]) {
-    let mut buf = [0u8; 2 * KYBER_SYM_BYTES];
-    let mut kr = [0u8; 2 * KYBER_SYM_BYTES];
-    let mut cmp = [0u8; KYBER_CIPHERTEXT_BYTES];
-    let mut pk = [0u8; KYBER_INDCPA_PUBLIC_KEY_BYTES];
+    let _ = decrypt_message_with_context(ss, ct, sk, None);
+}
 
-    pk.copy_from_slice(
-        &sk[KYBER_INDCPA_SECRET_KEY_BYTES..]
-            [..KYBER_INDCPA_PUBLIC_KEY_BYTES],
-    );
+// Decapsulation, optionally binding the coins to an application context.
+// Returns 1 if the re-encryption check failed and `ss` was derived from
+// the implicit rejection secret, 0 otherwise.
+pub(crate) fn decrypt_message_with_context(
+    ss: &mut [u8],
+    ct: &[u8],
+    sk: &[u8],
+    context: Option<&[u8]>,
+) -> u8 {
+    let pk = &sk[KYBER_INDCPA_SECRET_KEY_BYTES..]
+        [..KYBER_INDCPA_PUBLIC_KEY_BYTES];
+    decrypt_message_with_pk(ss, ct, sk, pk, context)
+}
+
+// Decapsulation using a public key the caller already extracted from `sk`
+pub(crate) fn decrypt_message_with_pk(
+    ss: &mut [u8],
+    ct: &[u8],
+    sk: &[u8],
+    pk: &[u8],
+    context: Option<&[u8]>,
+) -> u8 {
+    decrypt_message_prepared(ss, ct, sk, pk, None, context)
+}
 
-    indcpa_dec(&mut buf, ct, sk);
+// Decapsulation that re-encrypts with the matrix A of `pk` if it has
+// already been expanded, and expands it from `pk` otherwise
+pub(crate) fn decrypt_message_prepared(
+    ss: &mut [u8],
+    ct: &[u8],
+    sk: &[u8],
+    pk: &[u8],
+    at: Option<&PublicMatrix>,
+    context: Option<&[u8]>,
+) -> u8 {
+    let mut buf = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    let mut kr = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    let mut cmp = [0u8; KYBER_CIPHERTEXT_BYTES];
+
+    indcpa_dec(&mut buf[..], ct, sk);
 
     // Multitarget countermeasure for coins + contributory KEM
     const START: usize = KYBER_SECRET_KEY_BYTES - 2 * KYBER_SYM_BYTES;
     const END: usize = KYBER_SECRET_KEY_BYTES - KYBER_SYM_BYTES;
     buf[KYBER_SYM_BYTES..].copy_from_slice(&sk[START..END]);
-    hash_g(&mut kr, &buf, 2 * KYBER_SYM_BYTES);
+    hash_coins(&mut kr[..], &buf[..], context);
 
     // Coins are in kr[KYBER_SYM_BYTES..]
-    indcpa_enc(&mut cmp, &buf, &pk, &kr[KYBER_SYM_BYTES..]);
+    let coins = &kr[KYBER_SYM_BYTES..];
+    match at {
+        Some(at) => {
+            indcpa_enc_prepared(&mut cmp, &buf[..], pk, at, coins)
+        }
+        None => indcpa_enc(&mut cmp, &buf[..], pk, coins),
+    }
     let fail = verify(ct, &cmp, KYBER_CIPHERTEXT_BYTES);
 
     // Overwrite coins in kr with H(c)
-    hash_h(&mut kr[KYBER_SYM_BYTES..], ct, KYBER_CIPHERTEXT_BYTES);
+    hash_h_unchecked(
+        &mut kr[KYBER_SYM_BYTES..],
+        ct,
+        KYBER_CIPHERTEXT_BYTES,
+    );
 
     // Overwrite pre-k with z on re-encryption failure
-    cmov(&mut kr, &sk[END..], KYBER_SYM_BYTES, fail);
+    cmov(&mut kr[..], &sk[END..], KYBER_SYM_BYTES, fail);
 
     // Hash concatenation of pre-k and H(c) to derive the shared secret
-    kdf(ss, &kr, 2 * KYBER_SYM_BYTES);
+    kdf(ss, &kr[..], 2 * KYBER_SYM_BYTES);
+    fail
+}
+
+// IND-CPA decapsulation: derives the shared secret exactly like
+// `decrypt_message` on an honest ciphertext, but skips the
+// re-encryption check and the implicit rejection that goes with it
+#[cfg(feature = "fast-decap")]
+pub(crate) fn decrypt_message_no_reencrypt(
+    ss: &mut [u8],
+    ct: &[u8],
+    sk: &[u8],
+) {
+    let mut buf = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    let mut kr = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+
+    indcpa_dec(&mut buf[..], ct, sk);
+
+    const START: usize = KYBER_SECRET_KEY_BYTES - 2 * KYBER_SYM_BYTES;
+    const END: usize = KYBER_SECRET_KEY_BYTES - KYBER_SYM_BYTES;
+    buf[KYBER_SYM_BYTES..].copy_from_slice(&sk[START..END]);
+    hash_coins(&mut kr[..], &buf[..], None);
+
+    // Overwrite coins in kr with H(c)
+    hash_h_unchecked(
+        &mut kr[KYBER_SYM_BYTES..],
+        ct,
+        KYBER_CIPHERTEXT_BYTES,
+    );
+    kdf(ss, &kr[..], 2 * KYBER_SYM_BYTES);
+}
+
+// Stack buffer for secret intermediates, wiped on drop with `zeroize`
+#[cfg(feature = "zeroize")]
+pub(crate) fn secret_buf<const N: usize>() -> Zeroizing<[u8; N]> {
+    Zeroizing::new([0u8; N])
+}
+
+// Stack buffer for secret intermediates
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn secret_buf<const N: usize>() -> [u8; N] {
+    [0u8; N]
+}
+
+// Derives pre-k and coins as G(m || H(pk)), or as
+// G(H(context) || m || H(pk)) when bound to an application context.
+// Hashing the context to a fixed width keeps the encoding unambiguous,
+// so an empty context and a single zero byte bind different coins.
+fn hash_coins(kr: &mut [u8], buf: &[u8], context: Option<&[u8]>) {
+    match context {
+        None => hash_g_unchecked(kr, buf, 2 * KYBER_SYM_BYTES),
+        Some(context) => {
+            let mut ctx_buf = secret_buf::<{ 3 * KYBER_SYM_BYTES }>();
+            hash_h_unchecked(&mut ctx_buf[..], context, context.len());
+            ctx_buf[KYBER_SYM_BYTES..].copy_from_slice(buf);
+            hash_g_unchecked(kr, &ctx_buf[..], 3 * KYBER_SYM_BYTES);
+        }
+    }
 }
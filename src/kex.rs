@@ -1,8 +1,27 @@
 // Copyright © 2024 kyberlib. All rights reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::{kem::*, params::*, symmetric::kdf, KyberLibError};
+extern crate alloc;
+
+use crate::{
+    api::{is_trivial_public_key, wipe},
+    fips202::shake256_concat,
+    frame::{
+        frame_message, parse_frame, FRAME_AKE_INIT, FRAME_AKE_RESPONSE,
+        FRAME_UAKE_INIT, FRAME_UAKE_RESPONSE,
+    },
+    kem::*,
+    params::*,
+    rng::randombytes,
+    symmetric::{hash_h_unchecked, kdf},
+    KyberLibError,
+};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
 use rand_core::{CryptoRng, RngCore};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Unilateral Key Exchange Initiation Byte Length
 pub const UAKE_INIT_BYTES: usize =
@@ -14,6 +33,58 @@ pub const AKE_INIT_BYTES: usize =
     KYBER_PUBLIC_KEY_BYTES + KYBER_CIPHERTEXT_BYTES;
 /// Mutual Key Exchange Response Byte Length
 pub const AKE_RESPONSE_BYTES: usize = 2 * KYBER_CIPHERTEXT_BYTES;
+/// Serialized `UakeState` Byte Length
+pub const UAKE_STATE_BYTES: usize =
+    1 + KYBER_SHARED_SECRET_BYTES + KYBER_SECRET_KEY_BYTES;
+/// Serialized `AkeState` Byte Length
+pub const AKE_STATE_BYTES: usize =
+    UAKE_STATE_BYTES + KYBER_PUBLIC_KEY_BYTES + AKE_INIT_BYTES;
+
+// Domain separation suffixes of the UAKE key confirmation tags, one
+// per direction so a tag cannot be reflected back to its sender
+const CLIENT_CONFIRMATION_LABEL: &[u8] = b"client confirm";
+const SERVER_CONFIRMATION_LABEL: &[u8] = b"server confirm";
+
+/// Total bytes sent in both directions during a unilateral key exchange.
+pub const fn uake_total_bytes() -> usize {
+    UAKE_INIT_BYTES + UAKE_RESPONSE_BYTES
+}
+
+/// Total bytes sent in both directions during a mutual key exchange.
+pub const fn ake_total_bytes() -> usize {
+    AKE_INIT_BYTES + AKE_RESPONSE_BYTES
+}
+
+/// Splits a unilateral or mutual initiation into the client's ephemeral
+/// public key and the ciphertext that follows it.
+///
+/// Lets a relay inspect the embedded public key without hard-coding
+/// the offsets. `AkeSendInit` has the same layout, so this accepts
+/// either message.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let bob_keys = keypair(&mut rng)?;
+/// let init = Uake::new().client_init(&bob_keys.public, &mut rng)?;
+/// let (pk, ct) = split_init(&init);
+/// assert_eq!(pk.len(), KYBER_PUBLIC_KEY_BYTES);
+/// assert_eq!(ct.len(), KYBER_CIPHERTEXT_BYTES);
+/// # Ok(())}
+/// ```
+pub fn split_init(
+    init: &UakeSendInit,
+) -> (&[u8; KYBER_PUBLIC_KEY_BYTES], &[u8; KYBER_CIPHERTEXT_BYTES]) {
+    let (pk, ct) = init.split_at(KYBER_PUBLIC_KEY_BYTES);
+    (
+        <&[u8; KYBER_PUBLIC_KEY_BYTES]>::try_from(pk)
+            .expect("split at the public key length"),
+        <&[u8; KYBER_CIPHERTEXT_BYTES]>::try_from(ct)
+            .expect("remainder is the ciphertext length"),
+    )
+}
 
 /// Result of encapsulating a public key which includes the ciphertext and shared secret
 pub type Encapsulated = Result<
@@ -41,10 +112,440 @@ pub type AkeSendInit = [u8; AKE_INIT_BYTES];
 /// Bytes to send when responding to a mutual key exchange
 pub type AkeSendResponse = [u8; AKE_RESPONSE_BYTES];
 
+/// A public key usable as a map key, displayed as lowercase hex.
+///
+/// Wraps a [`PublicKey`] so it can index session tables in a
+/// `BTreeMap` or `HashMap` and be logged without extra formatting code.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let id = PublicKeyId::from(keys.public);
+/// assert_eq!(id.as_bytes(), &keys.public);
+/// assert!(id.to_string().starts_with(&format!("{:02x}", keys.public[0])));
+/// # Ok(())}
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PublicKeyId(PublicKey);
+
+impl PublicKeyId {
+    /// Returns the wrapped public key.
+    pub const fn as_bytes(&self) -> &PublicKey {
+        &self.0
+    }
+}
+
+impl From<PublicKey> for PublicKeyId {
+    fn from(public: PublicKey) -> Self {
+        Self(public)
+    }
+}
+
+impl From<PublicKeyId> for PublicKey {
+    fn from(id: PublicKeyId) -> Self {
+        id.0
+    }
+}
+
+impl core::fmt::Display for PublicKeyId {
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// A ciphertext whose length has been validated.
+///
+/// Built once from untrusted bytes with [`Ciphertext::try_from_slice`],
+/// it dereferences to `[u8]` and can be passed straight to
+/// `decapsulate` without repeating the length check.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let keys = keypair(&mut rng)?;
+/// let (ct, ss) = encapsulate(&keys.public, &mut rng)?;
+/// let received: &[u8] = &ct;
+/// let ciphertext = Ciphertext::try_from_slice(received)?;
+/// assert_eq!(decapsulate(&ciphertext, &keys.secret)?, ss);
+/// # Ok(())}
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Ciphertext([u8; KYBER_CIPHERTEXT_BYTES]);
+
+impl Ciphertext {
+    /// Validates and copies a ciphertext received as a byte slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if `bytes` is not
+    /// `KYBER_CIPHERTEXT_BYTES` long.
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self, KyberLibError> {
+        if bytes.len() != KYBER_CIPHERTEXT_BYTES {
+            return Err(KyberLibError::InvalidInput);
+        }
+        let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+        ct.copy_from_slice(bytes);
+        Ok(Self(ct))
+    }
+
+    /// Returns the ciphertext bytes.
+    pub const fn as_bytes(&self) -> &[u8; KYBER_CIPHERTEXT_BYTES] {
+        &self.0
+    }
+}
+
+impl From<[u8; KYBER_CIPHERTEXT_BYTES]> for Ciphertext {
+    fn from(ct: [u8; KYBER_CIPHERTEXT_BYTES]) -> Self {
+        Self(ct)
+    }
+}
+
+impl AsRef<[u8]> for Ciphertext {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl core::ops::Deref for Ciphertext {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The kind of key exchange a handshake message belongs to.
+///
+/// `UAKE_INIT_BYTES` and `AKE_INIT_BYTES` are equal, so the length of an
+/// initiation alone cannot tell the two exchanges apart.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HandshakeKind {
+    /// Unilaterally authenticated key exchange with `Uake`.
+    Uake,
+    /// Mutually authenticated key exchange with `Ake`.
+    Ake,
+}
+
+/// Returns the length of the server response for a key exchange kind.
+///
+/// Lets a transport pre-allocate the response buffer once it knows
+/// which exchange an initiation starts.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// assert_eq!(response_len(HandshakeKind::Uake), UAKE_RESPONSE_BYTES);
+/// assert_eq!(response_len(HandshakeKind::Ake), AKE_RESPONSE_BYTES);
+/// ```
+pub const fn response_len(kind: HandshakeKind) -> usize {
+    match kind {
+        HandshakeKind::Uake => UAKE_RESPONSE_BYTES,
+        HandshakeKind::Ake => AKE_RESPONSE_BYTES,
+    }
+}
+
+/// A handshake message tagged with the step of the exchange it belongs
+/// to.
+///
+/// `to_bytes` frames the message with `frame::frame_message`, so the
+/// peer can recover the variant from an incoming blob with
+/// `from_bytes` instead of inferring it from the length alone.
+///
+/// # Example
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let bob_keys = keypair(&mut rng)?;
+/// let mut alice = Uake::new();
+/// let init = alice.client_init(&bob_keys.public, &mut rng)?;
+/// let bytes = HandshakeMessage::UakeInit(init).to_bytes();
+/// assert_eq!(
+///     HandshakeMessage::from_bytes(&bytes)?,
+///     HandshakeMessage::UakeInit(init)
+/// );
+/// # Ok(())}
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HandshakeMessage {
+    /// Client initiation of a unilateral key exchange.
+    UakeInit(UakeSendInit),
+    /// Server response to a unilateral key exchange.
+    UakeResponse(UakeSendResponse),
+    /// Client initiation of a mutual key exchange.
+    AkeInit(AkeSendInit),
+    /// Server response to a mutual key exchange.
+    AkeResponse(AkeSendResponse),
+}
+
+impl HandshakeMessage {
+    /// Returns the frame kind tagging this variant.
+    pub const fn kind(&self) -> u8 {
+        match self {
+            HandshakeMessage::UakeInit(_) => FRAME_UAKE_INIT,
+            HandshakeMessage::UakeResponse(_) => FRAME_UAKE_RESPONSE,
+            HandshakeMessage::AkeInit(_) => FRAME_AKE_INIT,
+            HandshakeMessage::AkeResponse(_) => FRAME_AKE_RESPONSE,
+        }
+    }
+
+    /// Returns the message bytes without the frame header.
+    pub fn payload(&self) -> &[u8] {
+        match self {
+            HandshakeMessage::UakeInit(msg) => msg,
+            HandshakeMessage::UakeResponse(msg) => msg,
+            HandshakeMessage::AkeInit(msg) => msg,
+            HandshakeMessage::AkeResponse(msg) => msg,
+        }
+    }
+
+    /// Serializes the message as a tagged, length-prefixed frame.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        frame_message(self.kind(), self.payload())
+    }
+
+    /// Parses a frame produced by `to_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` for an unknown kind or
+    /// trailing bytes after the frame, and
+    /// `KyberLibError::InvalidLength` if the frame is truncated or its
+    /// payload does not match the size of the tagged variant.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KyberLibError> {
+        let (kind, payload, rest) = parse_frame(bytes)?;
+        if !rest.is_empty() {
+            return Err(KyberLibError::InvalidInput);
+        }
+        match kind {
+            FRAME_UAKE_INIT => {
+                Ok(HandshakeMessage::UakeInit(read_array(payload)?))
+            }
+            FRAME_UAKE_RESPONSE => {
+                Ok(HandshakeMessage::UakeResponse(read_array(payload)?))
+            }
+            FRAME_AKE_INIT => {
+                Ok(HandshakeMessage::AkeInit(read_array(payload)?))
+            }
+            FRAME_AKE_RESPONSE => {
+                Ok(HandshakeMessage::AkeResponse(read_array(payload)?))
+            }
+            _ => Err(KyberLibError::InvalidInput),
+        }
+    }
+}
+
+// Copies a payload into a fixed-size message, rejecting other lengths
+fn read_array<const N: usize>(
+    payload: &[u8],
+) -> Result<[u8; N], KyberLibError> {
+    if payload.len() != N {
+        return Err(KyberLibError::InvalidLength);
+    }
+    let mut msg = [0u8; N];
+    msg.copy_from_slice(payload);
+    Ok(msg)
+}
+
+/// Progress of a `Uake` or `Ake` key exchange.
+///
+/// Each step of the exchange checks the current state before touching
+/// its buffers, so calling the steps out of order returns
+/// `KyberLibError::HandshakeOutOfOrder` instead of deriving a shared
+/// secret from zeroed or stale ephemeral keys. Calling `client_init()`
+/// again while `AwaitingResponse` returns
+/// `KyberLibError::HandshakeInProgress` rather than overwriting the
+/// pending ephemeral key.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum HandshakeState {
+    /// No exchange in progress, `client_init()` or `server_receive()` comes next.
+    #[default]
+    Init,
+    /// `client_init()` has been sent, `client_confirm()` comes next.
+    AwaitingResponse,
+    /// The shared secret has been established.
+    Confirmed,
+}
+
+impl HandshakeState {
+    // Tag byte used by the serialized handshake states
+    fn to_byte(self) -> u8 {
+        match self {
+            HandshakeState::Init => 0,
+            HandshakeState::AwaitingResponse => 1,
+            HandshakeState::Confirmed => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, KyberLibError> {
+        match byte {
+            0 => Ok(HandshakeState::Init),
+            1 => Ok(HandshakeState::AwaitingResponse),
+            2 => Ok(HandshakeState::Confirmed),
+            _ => Err(KyberLibError::InvalidInput),
+        }
+    }
+}
+
 // Ephemeral keys
 type TempKey = [u8; KYBER_SHARED_SECRET_BYTES];
 type Eska = [u8; KYBER_SECRET_KEY_BYTES];
 
+// Key exchange options shared by `UakeBuilder` and `AkeBuilder`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct KexConfig {
+    // Hash of the context label, if any
+    context: Option<[u8; KYBER_SYM_BYTES]>,
+    // Reject malformed peer public keys
+    strict: bool,
+}
+
+impl KexConfig {
+    fn set_context(&mut self, label: &[u8]) {
+        let mut context = [0u8; KYBER_SYM_BYTES];
+        hash_h_unchecked(&mut context, label, label.len());
+        self.context = Some(context);
+    }
+
+    fn context(&self) -> Option<&[u8]> {
+        self.context.as_ref().map(|context| &context[..])
+    }
+
+    fn check_public_key(&self, pk: &[u8]) -> Result<(), KyberLibError> {
+        if self.strict && is_trivial_public_key(pk) {
+            return Err(KyberLibError::InvalidKey);
+        }
+        Ok(())
+    }
+}
+
+/// Configures a [`Uake`] before the exchange starts.
+///
+/// # Example:
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let bob_keys = keypair(&mut rng)?;
+/// let mut alice = UakeBuilder::new().context(b"session 42").build();
+/// let mut bob = UakeBuilder::new().context(b"session 42").build();
+/// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+/// let server_send = bob.server_receive(client_init, &bob_keys.secret, &mut rng)?;
+/// alice.client_confirm(server_send)?;
+/// assert_eq!(alice.shared_secret, bob.shared_secret);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UakeBuilder {
+    config: KexConfig,
+}
+
+impl UakeBuilder {
+    /// Creates a builder with no context and strict checks disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the exchange to an application context label.
+    ///
+    /// The label is mixed into every encapsulation and decapsulation of
+    /// the exchange, so both parties must use the same label to derive
+    /// the same shared secret.
+    pub fn context(mut self, label: &[u8]) -> Self {
+        self.config.set_context(label);
+        self
+    }
+
+    /// Rejects malformed peer public keys with `KyberLibError::InvalidKey`.
+    ///
+    /// When enabled, every public key received or targeted is checked
+    /// with [`is_trivial_public_key`], catching all-zero keys and keys
+    /// encoded for a different security level.
+    pub fn strict_length_check(mut self, strict: bool) -> Self {
+        self.config.strict = strict;
+        self
+    }
+
+    /// Builds the configured `Uake`.
+    pub fn build(self) -> Uake {
+        Uake {
+            config: self.config,
+            ..Uake::default()
+        }
+    }
+}
+
+/// Configures an [`Ake`] before the exchange starts.
+///
+/// # Example:
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let alice_keys = keypair(&mut rng)?;
+/// let bob_keys = keypair(&mut rng)?;
+/// let builder = AkeBuilder::new()
+///     .context(b"session 42")
+///     .strict_length_check(true);
+/// let mut alice = builder.build();
+/// let mut bob = builder.build();
+/// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+/// let server_send = bob.server_receive(client_init, &alice_keys.public, &bob_keys.secret, &mut rng)?;
+/// alice.client_confirm(server_send, &alice_keys.secret)?;
+/// assert_eq!(alice.shared_secret, bob.shared_secret);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AkeBuilder {
+    config: KexConfig,
+}
+
+impl AkeBuilder {
+    /// Creates a builder with no context and strict checks disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the exchange to an application context label.
+    ///
+    /// The label is mixed into every encapsulation and decapsulation of
+    /// the exchange, so both parties must use the same label to derive
+    /// the same shared secret.
+    pub fn context(mut self, label: &[u8]) -> Self {
+        self.config.set_context(label);
+        self
+    }
+
+    /// Rejects malformed peer public keys with `KyberLibError::InvalidKey`.
+    ///
+    /// When enabled, every public key received or targeted is checked
+    /// with [`is_trivial_public_key`], catching all-zero keys and keys
+    /// encoded for a different security level.
+    pub fn strict_length_check(mut self, strict: bool) -> Self {
+        self.config.strict = strict;
+        self
+    }
+
+    /// Builds the configured `Ake`.
+    pub fn build(self) -> Ake {
+        Ake {
+            config: self.config,
+            ..Ake::default()
+        }
+    }
+}
+
 /// Represents unilaterally authenticated key exchange between two parties.
 ///
 /// # Example:
@@ -65,6 +566,7 @@ type Eska = [u8; KYBER_SECRET_KEY_BYTES];
 /// # Ok(()) }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
 pub struct Uake {
     /// The resulting shared secret from a key exchange
     pub shared_secret: SharedSecret,
@@ -76,6 +578,15 @@ pub struct Uake {
     pub temp_key: TempKey,
     /// Ephemeral secret key
     pub eska: Eska,
+    // Options set through the builder
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    config: KexConfig,
+    // Current step of the exchange
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    state: HandshakeState,
+    // Whether this side initiated the exchange with client_init()
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    initiator: bool,
 }
 
 impl Default for Uake {
@@ -86,6 +597,9 @@ impl Default for Uake {
             send_b: [0u8; UAKE_RESPONSE_BYTES],
             temp_key: [0u8; KYBER_SHARED_SECRET_BYTES],
             eska: [0u8; KYBER_SECRET_KEY_BYTES],
+            config: KexConfig::default(),
+            state: HandshakeState::Init,
+            initiator: false,
         }
     }
 }
@@ -102,6 +616,168 @@ impl Uake {
         Self::default()
     }
 
+    /// Creates a new UAKE struct with random ephemeral key material.
+    ///
+    /// `temp_key` and `eska` are filled from `rng` up front, so a struct
+    /// used without `client_init()` never holds predictable all-zero
+    /// ephemerals. `client_init()` still overwrites them as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::RandomBytesGenerationSized` if the RNG fails.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let kex = Uake::with_rng(&mut rng)?;
+    /// assert_ne!(kex.temp_key, [0u8; KYBER_SHARED_SECRET_BYTES]);
+    /// # Ok(()) }
+    /// ```
+    pub fn with_rng<R>(rng: &mut R) -> Result<Self, KyberLibError>
+    where
+        R: CryptoRng + RngCore,
+    {
+        let mut kex = Self::default();
+        randombytes(&mut kex.temp_key, KYBER_SHARED_SECRET_BYTES, rng)?;
+        randombytes(&mut kex.eska, KYBER_SECRET_KEY_BYTES, rng)?;
+        Ok(kex)
+    }
+
+    /// Returns the current step of the key exchange.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let mut alice = Uake::new();
+    /// assert_eq!(alice.state(), HandshakeState::Init);
+    /// let bob_keys = keypair(&mut rng)?;
+    /// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+    /// assert_eq!(alice.state(), HandshakeState::AwaitingResponse);
+    /// # Ok(()) }
+    /// ```
+    pub fn state(&self) -> HandshakeState {
+        self.state
+    }
+
+    /// Copies the ephemeral keys and step of the exchange into a
+    /// [`UakeState`], e.g. to persist a handshake between events.
+    ///
+    /// # Security
+    ///
+    /// The saved state contains the ephemeral secret key. Anyone who
+    /// reads it can compute the shared secret of this exchange, so
+    /// store it only where the long-term secret key would be safe.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let bob_keys = keypair(&mut rng)?;
+    /// let mut alice = Uake::new();
+    /// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+    /// let saved = alice.save_state().to_bytes();
+    ///
+    /// let mut resumed = Uake::new();
+    /// resumed.restore_state(&UakeState::from_bytes(&saved)?);
+    /// let mut bob = Uake::new();
+    /// let server_send = bob.server_receive(client_init, &bob_keys.secret, &mut rng)?;
+    /// resumed.client_confirm(server_send)?;
+    /// assert_eq!(resumed.shared_secret, bob.shared_secret);
+    /// # Ok(()) }
+    /// ```
+    pub fn save_state(&self) -> UakeState {
+        UakeState {
+            temp_key: self.temp_key,
+            eska: self.eska,
+            state: self.state,
+        }
+    }
+
+    /// Resumes an exchange from a state saved with
+    /// [`save_state`](Uake::save_state).
+    ///
+    /// Builder options such as the context are not part of the state:
+    /// restore into a `Uake` built with the same options.
+    pub fn restore_state(&mut self, saved: &UakeState) {
+        self.temp_key = saved.temp_key;
+        self.eska = saved.eska;
+        self.state = saved.state;
+    }
+
+    /// Computes this side's key confirmation tag for the peer to check
+    /// with [`verify_key_confirmation`](Uake::verify_key_confirmation).
+    ///
+    /// The tag is `SHAKE256(shared_secret || "client confirm")` on the
+    /// side that called `client_init()` and
+    /// `SHAKE256(shared_secret || "server confirm")` on the side that
+    /// called `server_receive()`. Sending it adds an explicit
+    /// confirmation round, so each side learns the other derived the
+    /// same secret before any application traffic flows. The labels
+    /// differ per direction, so a tag reflected back to its sender
+    /// does not verify.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::HandshakeOutOfOrder` unless the exchange
+    /// is `Confirmed`.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let bob_keys = keypair(&mut rng)?;
+    /// let mut alice = Uake::new();
+    /// let mut bob = Uake::new();
+    /// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+    /// let server_send = bob.server_receive(client_init, &bob_keys.secret, &mut rng)?;
+    /// alice.client_confirm(server_send)?;
+    /// assert!(alice.verify_key_confirmation(&bob.key_confirmation()?)?);
+    /// assert!(bob.verify_key_confirmation(&alice.key_confirmation()?)?);
+    /// # Ok(()) }
+    /// ```
+    pub fn key_confirmation(&self) -> Result<[u8; 32], KyberLibError> {
+        self.confirmation_tag(self.initiator)
+    }
+
+    /// Checks the peer's [`key_confirmation`](Uake::key_confirmation)
+    /// tag against this side's shared secret in constant time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::HandshakeOutOfOrder` unless the exchange
+    /// is `Confirmed`.
+    pub fn verify_key_confirmation(
+        &self,
+        tag: &[u8; 32],
+    ) -> Result<bool, KyberLibError> {
+        let expected = self.confirmation_tag(!self.initiator)?;
+        Ok(bool::from(expected.ct_eq(tag)))
+    }
+
+    // Tag sent by the client if `client`, otherwise by the server
+    fn confirmation_tag(
+        &self,
+        client: bool,
+    ) -> Result<[u8; 32], KyberLibError> {
+        if self.state != HandshakeState::Confirmed {
+            return Err(KyberLibError::HandshakeOutOfOrder);
+        }
+        let label = if client {
+            CLIENT_CONFIRMATION_LABEL
+        } else {
+            SERVER_CONFIRMATION_LABEL
+        };
+        let mut tag = [0u8; 32];
+        shake256_concat(&mut tag, 32, &[&self.shared_secret, label]);
+        Ok(tag)
+    }
+
     /// Initiates a Unilaterally Authenticated Key Exchange.
     ///
     /// # Example:
@@ -122,13 +798,19 @@ impl Uake {
     where
         R: CryptoRng + RngCore,
     {
+        if self.state == HandshakeState::AwaitingResponse {
+            return Err(KyberLibError::HandshakeInProgress);
+        }
+        self.config.check_public_key(pubkey)?;
         uake_init_a(
             &mut self.send_a,
             &mut self.temp_key,
             &mut self.eska,
             pubkey,
             rng,
+            self.config.context(),
         )?;
+        self.state = HandshakeState::AwaitingResponse;
         Ok(self.send_a)
     }
 
@@ -154,16 +836,44 @@ impl Uake {
     where
         R: CryptoRng + RngCore,
     {
+        if self.state == HandshakeState::AwaitingResponse {
+            return Err(KyberLibError::HandshakeOutOfOrder);
+        }
+        self.config
+            .check_public_key(&send_a[..KYBER_PUBLIC_KEY_BYTES])?;
         uake_shared_b(
             &mut self.send_b,
             &mut self.shared_secret,
             &send_a,
             secretkey,
             rng,
+            self.config.context(),
         )?;
+        self.state = HandshakeState::Confirmed;
+        self.initiator = false;
         Ok(self.send_b)
     }
 
+    /// Like `server_receive`, but takes the initiation as a slice of
+    /// untrusted length, e.g. straight from a network buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidLength` unless `send_a` is exactly
+    /// `UAKE_INIT_BYTES` long, and otherwise the errors of
+    /// `server_receive`.
+    pub fn server_receive_slice<R>(
+        &mut self,
+        send_a: &[u8],
+        secretkey: &SecretKey,
+        rng: &mut R,
+    ) -> Result<UakeSendResponse, KyberLibError>
+    where
+        R: CryptoRng + RngCore,
+    {
+        self.server_receive(read_array(send_a)?, secretkey, rng)
+    }
+
     /// Decapsulates and authenticates the shared secret from the output of
     /// `server_receive()`.
     ///
@@ -184,16 +894,190 @@ impl Uake {
         &mut self,
         send_b: UakeSendResponse,
     ) -> Result<(), KyberLibError> {
+        if self.state != HandshakeState::AwaitingResponse {
+            return Err(KyberLibError::HandshakeOutOfOrder);
+        }
         uake_shared_a(
             &mut self.shared_secret,
             &send_b,
             &self.temp_key,
             &self.eska,
+            self.config.context(),
         )?;
+        self.state = HandshakeState::Confirmed;
+        self.initiator = true;
         Ok(())
     }
 }
 
+/// Client-side state of a unilateral key exchange driven by the
+/// `uake_*_into` functions.
+///
+/// Unlike `Uake`, it holds only the ephemeral keys; the handshake
+/// messages and shared secret live in buffers owned by the caller.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
+pub struct UakeState {
+    /// Ephemeral keys for the key exchange
+    pub temp_key: TempKey,
+    /// Ephemeral secret key
+    pub eska: Eska,
+    // Current step of the exchange
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    state: HandshakeState,
+}
+
+impl Default for UakeState {
+    fn default() -> Self {
+        UakeState {
+            temp_key: [0u8; KYBER_SHARED_SECRET_BYTES],
+            eska: [0u8; KYBER_SECRET_KEY_BYTES],
+            state: HandshakeState::Init,
+        }
+    }
+}
+
+impl UakeState {
+    /// Creates a new client state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current step of the key exchange.
+    pub fn state(&self) -> HandshakeState {
+        self.state
+    }
+
+    /// Serializes the state as the step tag, `temp_key` and `eska`.
+    ///
+    /// The bytes include the ephemeral secret key and must be kept
+    /// secret.
+    pub fn to_bytes(&self) -> [u8; UAKE_STATE_BYTES] {
+        let mut out = [0u8; UAKE_STATE_BYTES];
+        out[0] = self.state.to_byte();
+        out[1..1 + KYBER_SHARED_SECRET_BYTES]
+            .copy_from_slice(&self.temp_key);
+        out[1 + KYBER_SHARED_SECRET_BYTES..]
+            .copy_from_slice(&self.eska);
+        out
+    }
+
+    /// Parses a state serialized with [`to_bytes`](UakeState::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidLength` if `bytes` is not
+    /// `UAKE_STATE_BYTES` long, or `KyberLibError::InvalidInput` if the
+    /// step tag is unknown.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KyberLibError> {
+        if bytes.len() != UAKE_STATE_BYTES {
+            return Err(KyberLibError::InvalidLength);
+        }
+        let mut saved = UakeState::new();
+        saved.state = HandshakeState::from_byte(bytes[0])?;
+        saved
+            .temp_key
+            .copy_from_slice(&bytes[1..1 + KYBER_SHARED_SECRET_BYTES]);
+        saved
+            .eska
+            .copy_from_slice(&bytes[1 + KYBER_SHARED_SECRET_BYTES..]);
+        Ok(saved)
+    }
+}
+
+/// Initiates a unilateral key exchange, writing the message into `out`.
+///
+/// Equivalent to `Uake::client_init` without keeping a copy of the
+/// message, so `out` can be a transmit buffer.
+///
+/// # Example:
+/// ```
+/// # use kyberlib::*;
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut rng = rand::thread_rng();
+/// let bob_keys = keypair(&mut rng)?;
+///
+/// let mut alice = UakeState::new();
+/// let mut send_a = [0u8; UAKE_INIT_BYTES];
+/// uake_client_init_into(&mut send_a, &mut alice, &bob_keys.public, &mut rng)?;
+///
+/// let mut send_b = [0u8; UAKE_RESPONSE_BYTES];
+/// let mut bob_secret = [0u8; KYBER_SHARED_SECRET_BYTES];
+/// uake_server_receive_into(&mut send_b, &mut bob_secret, &send_a, &bob_keys.secret, &mut rng)?;
+///
+/// let mut alice_secret = [0u8; KYBER_SHARED_SECRET_BYTES];
+/// uake_client_confirm_into(&mut alice_secret, &mut alice, &send_b)?;
+/// assert_eq!(alice_secret, bob_secret);
+/// # Ok(()) }
+/// ```
+pub fn uake_client_init_into<R>(
+    out: &mut UakeSendInit,
+    state: &mut UakeState,
+    pubkey: &PublicKey,
+    rng: &mut R,
+) -> Result<(), KyberLibError>
+where
+    R: CryptoRng + RngCore,
+{
+    if state.state == HandshakeState::AwaitingResponse {
+        return Err(KyberLibError::HandshakeInProgress);
+    }
+    uake_init_a(
+        out,
+        &mut state.temp_key,
+        &mut state.eska,
+        pubkey,
+        rng,
+        None,
+    )?;
+    state.state = HandshakeState::AwaitingResponse;
+    Ok(())
+}
+
+/// Handles the output of `uake_client_init_into`, writing the response
+/// into `out` and the shared secret into `shared_secret`.
+///
+/// Equivalent to `Uake::server_receive`; the server side keeps no state
+/// between messages.
+pub fn uake_server_receive_into<R>(
+    out: &mut UakeSendResponse,
+    shared_secret: &mut SharedSecret,
+    send_a: &UakeSendInit,
+    secretkey: &SecretKey,
+    rng: &mut R,
+) -> Result<(), KyberLibError>
+where
+    R: CryptoRng + RngCore,
+{
+    uake_shared_b(out, shared_secret, send_a, secretkey, rng, None)
+}
+
+/// Decapsulates and authenticates the shared secret from the output of
+/// `uake_server_receive_into`, writing it into `shared_secret`.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::HandshakeOutOfOrder` unless `state` is
+/// awaiting a response.
+pub fn uake_client_confirm_into(
+    shared_secret: &mut SharedSecret,
+    state: &mut UakeState,
+    send_b: &UakeSendResponse,
+) -> Result<(), KyberLibError> {
+    if state.state != HandshakeState::AwaitingResponse {
+        return Err(KyberLibError::HandshakeOutOfOrder);
+    }
+    uake_shared_a(
+        shared_secret,
+        send_b,
+        &state.temp_key,
+        &state.eska,
+        None,
+    )?;
+    state.state = HandshakeState::Confirmed;
+    Ok(())
+}
+
 /// Represents mutually authenticated key exchange between two parties.
 ///
 /// # Example:
@@ -216,6 +1100,7 @@ impl Uake {
 /// # Ok(()) }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
 pub struct Ake {
     /// The resulting shared secret from a key exchange
     pub shared_secret: SharedSecret,
@@ -227,6 +1112,14 @@ pub struct Ake {
     pub temp_key: TempKey,
     /// Ephemeral secret key
     pub eska: Eska,
+    // Server public key the exchange was initiated with
+    server_pk: PublicKey,
+    // Options set through the builder
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    config: KexConfig,
+    // Current step of the exchange
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    state: HandshakeState,
 }
 
 impl Default for Ake {
@@ -237,6 +1130,9 @@ impl Default for Ake {
             send_b: [0u8; AKE_RESPONSE_BYTES],
             temp_key: [0u8; KYBER_SHARED_SECRET_BYTES],
             eska: [0u8; KYBER_SECRET_KEY_BYTES],
+            server_pk: [0u8; KYBER_PUBLIC_KEY_BYTES],
+            config: KexConfig::default(),
+            state: HandshakeState::Init,
         }
     }
 }
@@ -253,6 +1149,85 @@ impl Ake {
         Self::default()
     }
 
+    /// Creates a new AKE struct with random ephemeral key material.
+    ///
+    /// `temp_key` and `eska` are filled from `rng` up front, so a struct
+    /// used without `client_init()` never holds predictable all-zero
+    /// ephemerals. `client_init()` still overwrites them as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::RandomBytesGenerationSized` if the RNG fails.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let kex = Ake::with_rng(&mut rng)?;
+    /// assert_ne!(kex.temp_key, [0u8; KYBER_SHARED_SECRET_BYTES]);
+    /// # Ok(()) }
+    /// ```
+    pub fn with_rng<R>(rng: &mut R) -> Result<Self, KyberLibError>
+    where
+        R: CryptoRng + RngCore,
+    {
+        let mut kex = Self::default();
+        randombytes(&mut kex.temp_key, KYBER_SHARED_SECRET_BYTES, rng)?;
+        randombytes(&mut kex.eska, KYBER_SECRET_KEY_BYTES, rng)?;
+        Ok(kex)
+    }
+
+    /// Returns the current step of the key exchange.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// let mut rng = rand::thread_rng();
+    /// let mut alice = Ake::new();
+    /// assert_eq!(alice.state(), HandshakeState::Init);
+    /// let bob_keys = keypair(&mut rng)?;
+    /// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+    /// assert_eq!(alice.state(), HandshakeState::AwaitingResponse);
+    /// # Ok(()) }
+    /// ```
+    pub fn state(&self) -> HandshakeState {
+        self.state
+    }
+
+    /// Copies the ephemeral keys, the server public key, the sent
+    /// initiation and the step of the exchange into an [`AkeState`],
+    /// e.g. to persist a handshake between events.
+    ///
+    /// # Security
+    ///
+    /// The saved state contains the ephemeral secret key. Anyone who
+    /// reads it can compute the shared secret of this exchange, so
+    /// store it only where the long-term secret key would be safe.
+    pub fn save_state(&self) -> AkeState {
+        AkeState {
+            temp_key: self.temp_key,
+            eska: self.eska,
+            server_pk: self.server_pk,
+            send_a: self.send_a,
+            state: self.state,
+        }
+    }
+
+    /// Resumes an exchange from a state saved with
+    /// [`save_state`](Ake::save_state).
+    ///
+    /// Builder options such as the context are not part of the state:
+    /// restore into an `Ake` built with the same options.
+    pub fn restore_state(&mut self, saved: &AkeState) {
+        self.temp_key = saved.temp_key;
+        self.eska = saved.eska;
+        self.server_pk = saved.server_pk;
+        self.send_a = saved.send_a;
+        self.state = saved.state;
+    }
+
     /// Initiates a Mutually Authenticated Key Exchange.
     ///
     /// # Example:
@@ -273,16 +1248,74 @@ impl Ake {
     where
         R: CryptoRng + RngCore,
     {
+        if self.state == HandshakeState::AwaitingResponse {
+            return Err(KyberLibError::HandshakeInProgress);
+        }
+        self.config.check_public_key(pubkey)?;
         ake_init_a(
             &mut self.send_a,
             &mut self.temp_key,
             &mut self.eska,
             pubkey,
             rng,
+            self.config.context(),
         )?;
+        self.server_pk = *pubkey;
+        self.state = HandshakeState::AwaitingResponse;
         Ok(self.send_a)
     }
 
+    /// Checks that a response is well formed before `client_confirm()`
+    /// accepts it. It does not authenticate the server.
+    ///
+    /// Returns `true` only if this exchange is awaiting a response, was
+    /// initiated with `expected_server_pk`, and the first ciphertext of
+    /// `send_b` passes the re-encryption check under the ephemeral key,
+    /// so it was not corrupted or tampered with in transit. A `false`
+    /// result lets the caller abort before a secret is stored.
+    ///
+    /// This does not prove the responder holds the server's secret key.
+    /// Anyone can encapsulate to the client's public keys; the server is
+    /// authenticated implicitly, because only the holder of the secret
+    /// key derives the same shared secret. Follow `client_confirm()`
+    /// with a key confirmation message if explicit proof is needed.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// # let mut rng = rand::thread_rng();
+    /// let mut alice = Ake::new();
+    /// let mut bob = Ake::new();
+    /// let alice_keys = keypair(&mut rng)?;
+    /// let bob_keys = keypair(&mut rng)?;
+    /// let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+    /// let server_send = bob.server_receive(client_init, &alice_keys.public, &bob_keys.secret, &mut rng)?;
+    /// assert!(alice.response_well_formed(&server_send, &bob_keys.public));
+    /// alice.client_confirm(server_send, &alice_keys.secret)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn response_well_formed(
+        &self,
+        send_b: &AkeSendResponse,
+        expected_server_pk: &PublicKey,
+    ) -> bool {
+        if self.state != HandshakeState::AwaitingResponse {
+            return false;
+        }
+        let same_server =
+            self.server_pk[..].ct_eq(&expected_server_pk[..]);
+        let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+        let fail = decrypt_message_with_context(
+            &mut ss,
+            &send_b[..KYBER_CIPHERTEXT_BYTES],
+            &self.eska,
+            self.config.context(),
+        );
+        wipe(&mut ss);
+        bool::from(same_server & fail.ct_eq(&0))
+    }
+
     /// Handles and authenticates the output of a `client_init()` request.
     ///
     /// # Example:
@@ -307,6 +1340,12 @@ impl Ake {
     where
         R: CryptoRng + RngCore,
     {
+        if self.state == HandshakeState::AwaitingResponse {
+            return Err(KyberLibError::HandshakeOutOfOrder);
+        }
+        self.config.check_public_key(pubkey)?;
+        self.config
+            .check_public_key(&ake_send_a[..KYBER_PUBLIC_KEY_BYTES])?;
         ake_shared_b(
             &mut self.send_b,
             &mut self.shared_secret,
@@ -314,10 +1353,39 @@ impl Ake {
             secretkey,
             pubkey,
             rng,
+            self.config.context(),
         )?;
+        self.send_a = ake_send_a;
+        self.state = HandshakeState::Confirmed;
         Ok(self.send_b)
     }
 
+    /// Like `server_receive`, but takes the initiation as a slice of
+    /// untrusted length, e.g. straight from a network buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidLength` unless `ake_send_a` is
+    /// exactly `AKE_INIT_BYTES` long, and otherwise the errors of
+    /// `server_receive`.
+    pub fn server_receive_slice<R>(
+        &mut self,
+        ake_send_a: &[u8],
+        pubkey: &PublicKey,
+        secretkey: &SecretKey,
+        rng: &mut R,
+    ) -> Result<AkeSendResponse, KyberLibError>
+    where
+        R: CryptoRng + RngCore,
+    {
+        self.server_receive(
+            read_array(ake_send_a)?,
+            pubkey,
+            secretkey,
+            rng,
+        )
+    }
+
     /// Decapsulates and authenticates the shared secret from the output of
     /// `server_receive()`.
     ///
@@ -340,15 +1408,148 @@ impl Ake {
         send_b: AkeSendResponse,
         secretkey: &SecretKey,
     ) -> Result<(), KyberLibError> {
+        if self.state != HandshakeState::AwaitingResponse {
+            return Err(KyberLibError::HandshakeOutOfOrder);
+        }
         ake_shared_a(
             &mut self.shared_secret,
             &send_b,
             &self.temp_key,
             &self.eska,
             secretkey,
+            self.config.context(),
         )?;
+        self.send_b = send_b;
+        self.state = HandshakeState::Confirmed;
         Ok(())
     }
+
+    /// Returns a channel-binding value for the completed exchange.
+    ///
+    /// Hashes the full transcript `send_a || send_b || shared_secret`
+    /// with `hash_h`, so higher layers can detect a spliced handshake
+    /// by comparing it out of band or mixing it into their own
+    /// authentication. Both parties obtain the same value once the
+    /// exchange is `Confirmed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::HandshakeOutOfOrder` unless the exchange
+    /// is `Confirmed`.
+    ///
+    /// # Example:
+    /// ```
+    /// # use kyberlib::*;
+    /// # fn main() -> Result<(), KyberLibError> {
+    /// # let mut rng = rand::thread_rng();
+    /// # let mut alice = Ake::new();
+    /// # let mut bob = Ake::new();
+    /// # let alice_keys = keypair(&mut rng)?;
+    /// # let bob_keys = keypair(&mut rng)?;
+    /// # let client_init = alice.client_init(&bob_keys.public, &mut rng)?;
+    /// let server_send = bob.server_receive(client_init, &alice_keys.public, &bob_keys.secret, &mut rng)?;
+    /// alice.client_confirm(server_send, &alice_keys.secret)?;
+    /// assert_eq!(alice.channel_binding()?, bob.channel_binding()?);
+    /// # Ok(()) }
+    /// ```
+    pub fn channel_binding(
+        &self,
+    ) -> Result<[u8; KYBER_SYM_BYTES], KyberLibError> {
+        if self.state != HandshakeState::Confirmed {
+            return Err(KyberLibError::HandshakeOutOfOrder);
+        }
+        let mut transcript = [0u8; AKE_INIT_BYTES
+            + AKE_RESPONSE_BYTES
+            + KYBER_SHARED_SECRET_BYTES];
+        transcript[..AKE_INIT_BYTES].copy_from_slice(&self.send_a);
+        transcript[AKE_INIT_BYTES..AKE_INIT_BYTES + AKE_RESPONSE_BYTES]
+            .copy_from_slice(&self.send_b);
+        transcript[AKE_INIT_BYTES + AKE_RESPONSE_BYTES..]
+            .copy_from_slice(&self.shared_secret);
+        let mut out = [0u8; KYBER_SYM_BYTES];
+        hash_h_unchecked(&mut out, &transcript, transcript.len());
+        wipe(&mut transcript);
+        Ok(out)
+    }
+}
+
+/// Saved state of a mutual key exchange, from [`Ake::save_state`].
+///
+/// Holds the ephemeral keys, the server public key the exchange was
+/// initiated with, the initiation sent to it and the step of the
+/// exchange.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
+pub struct AkeState {
+    /// Ephemeral keys for the key exchange
+    pub temp_key: TempKey,
+    /// Ephemeral secret key
+    pub eska: Eska,
+    // Server public key the exchange was initiated with
+    server_pk: PublicKey,
+    // Initiation sent by client_init(), hashed by channel_binding()
+    send_a: AkeSendInit,
+    // Current step of the exchange
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    state: HandshakeState,
+}
+
+impl AkeState {
+    /// Returns the current step of the key exchange.
+    pub fn state(&self) -> HandshakeState {
+        self.state
+    }
+
+    /// Serializes the state as the step tag, `temp_key`, `eska`, the
+    /// server public key and the sent initiation.
+    ///
+    /// The bytes include the ephemeral secret key and must be kept
+    /// secret.
+    pub fn to_bytes(&self) -> [u8; AKE_STATE_BYTES] {
+        let mut out = [0u8; AKE_STATE_BYTES];
+        let uake = UakeState {
+            temp_key: self.temp_key,
+            eska: self.eska,
+            state: self.state,
+        };
+        out[..UAKE_STATE_BYTES].copy_from_slice(&uake.to_bytes());
+        out[UAKE_STATE_BYTES
+            ..UAKE_STATE_BYTES + KYBER_PUBLIC_KEY_BYTES]
+            .copy_from_slice(&self.server_pk);
+        out[UAKE_STATE_BYTES + KYBER_PUBLIC_KEY_BYTES..]
+            .copy_from_slice(&self.send_a);
+        out
+    }
+
+    /// Parses a state serialized with [`to_bytes`](AkeState::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidLength` if `bytes` is not
+    /// `AKE_STATE_BYTES` long, or `KyberLibError::InvalidInput` if the
+    /// step tag is unknown.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KyberLibError> {
+        if bytes.len() != AKE_STATE_BYTES {
+            return Err(KyberLibError::InvalidLength);
+        }
+        let uake = UakeState::from_bytes(&bytes[..UAKE_STATE_BYTES])?;
+        let mut server_pk = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        server_pk.copy_from_slice(
+            &bytes[UAKE_STATE_BYTES
+                ..UAKE_STATE_BYTES + KYBER_PUBLIC_KEY_BYTES],
+        );
+        let mut send_a = [0u8; AKE_INIT_BYTES];
+        send_a.copy_from_slice(
+            &bytes[UAKE_STATE_BYTES + KYBER_PUBLIC_KEY_BYTES..],
+        );
+        Ok(AkeState {
+            temp_key: uake.temp_key,
+            eska: uake.eska,
+            server_pk,
+            send_a,
+            state: uake.state,
+        })
+    }
 }
 
 // Unilaterally Authenticated Key Exchange initiation
@@ -358,17 +1559,19 @@ fn uake_init_a<R>(
     sk: &mut [u8],
     pkb: &[u8],
     rng: &mut R,
+    context: Option<&[u8]>,
 ) -> Result<(), KyberLibError>
 where
     R: CryptoRng + RngCore,
 {
     generate_key_pair(send, sk, rng, None)?;
-    encrypt_message(
+    encrypt_message_with_context(
         &mut send[KYBER_PUBLIC_KEY_BYTES..],
         tk,
         pkb,
         rng,
         None,
+        context,
     )?;
     Ok(())
 }
@@ -380,18 +1583,27 @@ fn uake_shared_b<R>(
     recv: &[u8],
     skb: &[u8],
     rng: &mut R,
+    context: Option<&[u8]>,
 ) -> Result<(), KyberLibError>
 where
     R: CryptoRng + RngCore,
 {
-    let mut buf = [0u8; 2 * KYBER_SYM_BYTES];
-    encrypt_message(send, &mut buf, recv, rng, None)?;
-    decrypt_message(
+    let mut buf = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    encrypt_message_with_context(
+        send,
+        &mut buf[..],
+        recv,
+        rng,
+        None,
+        context,
+    )?;
+    let _ = decrypt_message_with_context(
         &mut buf[KYBER_SYM_BYTES..],
         &recv[KYBER_PUBLIC_KEY_BYTES..],
         skb,
+        context,
     );
-    kdf(k, &buf, 2 * KYBER_SYM_BYTES);
+    kdf(k, &buf[..], 2 * KYBER_SYM_BYTES);
     Ok(())
 }
 
@@ -401,11 +1613,13 @@ fn uake_shared_a(
     recv: &[u8],
     tk: &[u8],
     sk: &[u8],
+    context: Option<&[u8]>,
 ) -> Result<(), KyberLibError> {
-    let mut buf = [0u8; 2 * KYBER_SYM_BYTES];
-    decrypt_message(&mut buf, recv, sk);
+    let mut buf = secret_buf::<{ 2 * KYBER_SYM_BYTES }>();
+    let _ =
+        decrypt_message_with_context(&mut buf[..], recv, sk, context);
     buf[KYBER_SYM_BYTES..].copy_from_slice(tk);
-    kdf(k, &buf, 2 * KYBER_SYM_BYTES);
+    kdf(k, &buf[..], 2 * KYBER_SYM_BYTES);
     Ok(())
 }
 
@@ -416,17 +1630,19 @@ fn ake_init_a<R>(
     sk: &mut [u8],
     pkb: &[u8],
     rng: &mut R,
+    context: Option<&[u8]>,
 ) -> Result<(), KyberLibError>
 where
     R: CryptoRng + RngCore,
 {
     generate_key_pair(send, sk, rng, None)?;
-    encrypt_message(
+    encrypt_message_with_context(
         &mut send[KYBER_PUBLIC_KEY_BYTES..],
         tk,
         pkb,
         rng,
         None,
+        context,
     )?;
     Ok(())
 }
@@ -439,25 +1655,35 @@ fn ake_shared_b<R>(
     skb: &[u8],
     pka: &[u8],
     rng: &mut R,
+    context: Option<&[u8]>,
 ) -> Result<(), KyberLibError>
 where
     R: CryptoRng + RngCore,
 {
-    let mut buf = [0u8; 3 * KYBER_SYM_BYTES];
-    encrypt_message(send, &mut buf, recv, rng, None)?;
-    encrypt_message(
+    let mut buf = secret_buf::<{ 3 * KYBER_SYM_BYTES }>();
+    encrypt_message_with_context(
+        send,
+        &mut buf[..],
+        recv,
+        rng,
+        None,
+        context,
+    )?;
+    encrypt_message_with_context(
         &mut send[KYBER_CIPHERTEXT_BYTES..],
         &mut buf[KYBER_SYM_BYTES..],
         pka,
         rng,
         None,
+        context,
     )?;
-    decrypt_message(
+    let _ = decrypt_message_with_context(
         &mut buf[2 * KYBER_SYM_BYTES..],
         &recv[KYBER_PUBLIC_KEY_BYTES..],
         skb,
+        context,
     );
-    kdf(k, &buf, 3 * KYBER_SYM_BYTES);
+    kdf(k, &buf[..], 3 * KYBER_SYM_BYTES);
     Ok(())
 }
 
@@ -468,15 +1694,22 @@ fn ake_shared_a(
     tk: &[u8],
     sk: &[u8],
     ska: &[u8],
+    context: Option<&[u8]>,
 ) -> Result<(), KyberLibError> {
-    let mut buf = [0u8; 3 * KYBER_SYM_BYTES];
-    decrypt_message(&mut buf, recv, sk);
-    decrypt_message(
+    let mut buf = secret_buf::<{ 3 * KYBER_SYM_BYTES }>();
+    let _ = decrypt_message_with_context(
+        &mut buf[..],
+        &recv[..KYBER_CIPHERTEXT_BYTES],
+        sk,
+        context,
+    );
+    let _ = decrypt_message_with_context(
         &mut buf[KYBER_SYM_BYTES..],
         &recv[KYBER_CIPHERTEXT_BYTES..],
         ska,
+        context,
     );
     buf[2 * KYBER_SYM_BYTES..].copy_from_slice(tk);
-    kdf(k, &buf, 3 * KYBER_SYM_BYTES);
+    kdf(k, &buf[..], 3 * KYBER_SYM_BYTES);
     Ok(())
 }
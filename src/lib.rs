@@ -16,11 +16,45 @@
 //! | `kyber512`  | Enables Kyber512 mode, providing a security level roughly equivalent to AES-128.                                                                                                |
 //! | `kyber1024` | Enables Kyber1024 mode, offering a security level roughly equivalent to AES-256.                   |
 //! | `90s`       | Activates 90's mode, which uses SHA2 and AES-CTR as a replacement for SHAKE. This may provide hardware speedups on certain architectures.                                                           |
-//! | `avx2`      | On x86_64 platforms, enables the optimized AVX2 version. This flag causes a compile error on other architectures. |
+//! | `avx2`      | On x86_64 platforms, enables the optimized AVX2 version. This flag causes a compile error on other architectures, so only enable it for x86_64 targets (e.g. in a `[target.'cfg(target_arch = "x86_64")'.dependencies]` entry when cross-compiling). |
 //! | `wasm`      | Enables support for compiling to WASM targets. |
 //! | `nasm`      | Uses Netwide Assembler (NASM) AVX2 code instead of GNU Assembler (GAS) for portability. Requires a NASM compiler: <https://www.nasm.us/> |
 //! | `zeroize`   | Automatically zeroes out key exchange structs on drop using the [zeroize](https://docs.rs/zeroize/latest/zeroize/) crate |
 //! | `std`       | Enables the standard library (std). |
+//! | `turboshake` | Replaces the SHAKE128 XOF and SHAKE256 PRF with the 12-round TurboSHAKE variants for faster key generation. **Not interoperable** with standard Kyber: keys, ciphertexts and shared secrets only work with other `turboshake` builds. |
+//! | `metrics`   | Times `keypair`, `encapsulate` and `decapsulate` and reports each to a hook installed with `metrics::set_metrics_hook`. Implies `std`. |
+//! | `debug-internals` | Adds the `hazmat` module with `decode_public_poly`/`encode_public_poly` for diffing coefficient decodings against other implementations, `derive_with_intermediates` for auditing seeded key derivation, and `last_rejection_count` for timing analysis of the rejection sampler. Implies `std`. Debugging aid only. |
+//! | `interop-tests` | Runs `tests/interop_pqc.rs`, which checks that keys, ciphertexts and shared secrets are byte-identical to `pqc_kyber` 0.7.x for the same seeds (Kyber768, non-90s builds). Test-only. |
+//! | `ct-tests`  | Runs `tests/ct_decapsulate.rs`, a dudect-style statistical test that decapsulating valid and invalid ciphertexts takes the same time. Test-only; the test is ignored by default, so run it with `--release` and `-- --ignored`. |
+//! | `rayon`     | Adds `keypair_batch_par`, which derives a seeded batch of keypairs across the rayon thread pool with the same output as `keypair_batch`. |
+//! | `fast-decap` | Adds `decapsulate_no_reencrypt`, which skips the re-encryption check. **Downgrades security to IND-CPA**; only for ciphertexts that arrive over an already-authenticated channel. |
+//! | `encoding`  | Adds `encode_base32`/`decode_base32` for sharing public keys as text, e.g. in QR codes. Works without `std`. |
+//! | `nist-drbg` | Adds `rng::NistDrbg`, the NIST AES-256 CTR_DRBG, so `keypair` and `encapsulate` reproduce the official KAT vectors from their seeds. Testing only. |
+//! | `testing`   | Adds `testing::FailingRng` and `testing::CountingRng` so downstream crates can unit-test their handling of RNG failures, and `testing::labeled_rng` for reproducible RNG streams named by a label. |
+//! | `hkdf`      | Adds `expand_hkdf`, which expands a shared secret with HKDF-SHA256 (RFC 5869). |
+//! | `cbor`      | Adds the `cbor` module, which encodes keypairs and handshake messages as compact CBOR maps with integer keys via [ciborium](https://docs.rs/ciborium). Implies `std`. |
+//! | `capi`      | Exports `kyberlib_keypair`, `kyberlib_encapsulate` and `kyberlib_decapsulate` as `extern "C"` functions returning status codes. Generate the C header with `cbindgen --config cbindgen.toml --crate kyberlib --output kyberlib.h`. |
+//! | `hazmat`    | Exports low-level primitives such as the IND-CPA constants, the constant-time `hazmat::ct_select` and the IND-CPA-only `hazmat::decapsulate_cpa`, `hazmat::cpa_encrypt` and `hazmat::cpa_decrypt`. **Use with caution.** |
+//!
+//! ## Supported targets
+//!
+//! The reference implementation is portable Rust and produces the same
+//! keys, ciphertexts and shared secrets on every target: all byte
+//! packing goes through explicit little-endian conversions, never
+//! through the host's native byte order. CI tests it on x86_64 Linux,
+//! and it is expected to work unchanged on aarch64 and on 32-bit and
+//! big-endian targets such as ARM Cortex-M, RISC-V, MIPS and PowerPC. The `avx2`
+//! backend is limited to x86_64, and `wasm` to wasm32.
+//!
+//! ## Kyber version
+//!
+//! KyberLib implements the NIST Round 3 specification of Kyber
+//! (v3.02): encapsulation hashes the random message before use, and the
+//! shared secret is `KDF(K || H(c))`. Keys, ciphertexts and shared
+//! secrets therefore interoperate with existing Round 3 deployments,
+//! and `tests/test_nist_kat.rs` (run with `--features nist-drbg`)
+//! reproduces the Round 3 NIST KAT vectors. It is not the final ML-KEM of FIPS 203, which drops both
+//! hashing steps.
 //!
 //! ## Usage
 //!
@@ -36,6 +70,13 @@
 //! use kyberlib::*;
 //! ```
 //!
+//! or, for just the key encapsulation functions, the key exchange
+//! structs, the error type and the size constants:
+//!
+//! ```rust
+//! use kyberlib::prelude::*;
+//! ```
+//!
 //! ### Key Encapsulation
 //!
 //! Generate key pairs and encapsulate a shared secret between two parties:
@@ -150,6 +191,7 @@
 //! - **InvalidKey** - Error when generating keys.
 //! - **Decapsulation** - The ciphertext was unable to be authenticated. The shared secret was not decapsulated.
 //! - **RandomBytesGeneration** - Error trying to fill random bytes (i.e., external (hardware) RNG modules can fail).
+//! - **RandomBytesGenerationSized** - The RNG failed while filling the given number of bytes.
 //!
 #![doc(
     html_favicon_url = "https://kura.pro/kyberlib/images/favicon.ico",
@@ -164,6 +206,19 @@
 #[cfg(all(feature = "kyber1024", feature = "kyber512"))]
 compile_error!("Only one security level can be specified");
 
+#[cfg(all(feature = "avx2", not(target_arch = "x86_64")))]
+compile_error!(
+    "the `avx2` feature requires target_arch = x86_64; disable it for this target"
+);
+
+#[cfg(all(
+    feature = "turboshake",
+    any(feature = "90s", feature = "avx2")
+))]
+compile_error!(
+    "The turboshake feature only applies to the reference SHAKE backend"
+);
+
 #[cfg(all(target_arch = "x86_64", feature = "avx2"))]
 mod avx2;
 #[cfg(all(target_arch = "x86_64", feature = "avx2"))]
@@ -183,10 +238,22 @@ pub use reference::indcpa;
 /// WebAssembly bindings for the KyberLib library.
 pub mod wasm;
 
+#[cfg(feature = "capi")]
+/// C ABI bindings for the KyberLib library.
+pub mod capi;
+
 /// API for the KyberLib library.
 pub mod api;
+#[cfg(feature = "cbor")]
+/// CBOR encodings of keypairs and handshake messages.
+pub mod cbor;
 /// Error types for the KyberLib library.
 pub mod error;
+/// Framing of handshake messages for the KyberLib library.
+pub mod frame;
+#[cfg(any(feature = "debug-internals", feature = "hazmat"))]
+/// Low-level operations, behind `debug-internals` or `hazmat`.
+pub mod hazmat;
 /// Key encapsulation module for the KyberLib library.
 pub mod kem;
 /// Key exchange structs for the KyberLib library.
@@ -194,13 +261,24 @@ pub mod kex;
 
 /// Macro utilities for the KyberLib library.
 pub mod macros;
+#[cfg(feature = "metrics")]
+/// Timing hooks for the KyberLib library.
+pub mod metrics;
 /// Parameters for the KyberLib library.
 pub mod params;
+/// Common imports for the KyberLib library.
+pub mod prelude;
 
 /// Random number generators for the KyberLib library.
 pub mod rng;
+#[cfg(feature = "fips202-export")]
+/// SHA3 and SHAKE primitives for the KyberLib library.
+pub mod sha3;
 /// Symmetric key encapsulation module for the KyberLib library.
 pub mod symmetric;
+#[cfg(feature = "testing")]
+/// Failing, counting and labelled RNGs for tests.
+pub mod testing;
 
 /// WebAssembly bindings for the KyberLib library.
 pub mod wasm;
@@ -209,9 +287,10 @@ pub use api::*;
 pub use error::KyberLibError;
 pub use kex::*;
 pub use params::{
-    KYBER_90S, KYBER_CIPHERTEXT_BYTES, KYBER_PUBLIC_KEY_BYTES,
-    KYBER_SECRET_KEY_BYTES, KYBER_SECURITY_PARAMETER,
-    KYBER_SHARED_SECRET_BYTES, KYBER_SYM_BYTES,
+    KYBER_90S, KYBER_CIPHERTEXT_BYTES, KYBER_DERIVE_SEED_BYTES,
+    KYBER_PUBLIC_KEY_BYTES, KYBER_SECRET_KEY_BYTES,
+    KYBER_SECURITY_PARAMETER, KYBER_SHARED_SECRET_BYTES,
+    KYBER_SYM_BYTES,
 };
 pub use rand_core::{CryptoRng, RngCore};
 
@@ -224,3 +303,80 @@ pub use rand_core::{CryptoRng, RngCore};
     feature = "benchmarking"
 ))]
 pub use kem::*;
+
+/// Reports whether the compiled implementation processes secret data in
+/// constant time.
+///
+/// The answer depends on the implementation path this build compiles:
+///
+/// - **Reference backend** (the default): `true`. The arithmetic uses
+///   masks and conditional moves, and compresses coefficients with a
+///   multiply-and-shift instead of a division by `q`.
+/// - **AVX2 backend** (`avx2` on x86_64): `true`. The assembly uses
+///   vector masks and blends with no secret-dependent branches.
+/// - **Symmetric primitives**: Keccak has no data-dependent timing, and
+///   in `90s` mode the reference AES-256-CTR is bitsliced while
+///   `90s-fixslice` uses the fixsliced RustCrypto AES, so neither
+///   changes the answer.
+/// - **wasm32 targets**: `false`. The source is the same, but
+///   WebAssembly engines make no timing guarantees for the instructions
+///   it compiles to, so a JIT may reintroduce secret-dependent timing.
+///
+/// The rejection sampling that expands the matrix `A` runs for a
+/// variable number of iterations, but it only consumes the public seed
+/// carried in the public key, so its timing reveals nothing secret and
+/// does not make this `false`. Constant-time properties of the source
+/// can still be undone by the compiler or the target CPU (e.g.
+/// variable-latency multipliers), which this function cannot detect.
+///
+/// # Example
+/// ```
+/// const CONSTANT_TIME: bool = kyberlib::is_constant_time();
+/// assert_eq!(CONSTANT_TIME, !cfg!(target_arch = "wasm32"));
+/// ```
+pub const fn is_constant_time() -> bool {
+    // Both arithmetic backends and every symmetric primitive avoid
+    // secret-dependent timing; only the wasm32 target gives it up
+    !cfg!(target_arch = "wasm32")
+}
+
+/// The compile-time configuration of this build of KyberLib.
+///
+/// Returned by [`build_info`] so a deployed binary can report what it
+/// was built with, e.g. in a startup log line or a telemetry tag.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BuildInfo {
+    /// The compiled Kyber parameter set.
+    pub level: params::SecurityLevel,
+    /// Whether 90's mode (AES-CTR and SHA2) replaces SHAKE.
+    pub mode_90s: bool,
+    /// Whether the AVX2 implementation is compiled in.
+    pub avx2: bool,
+    /// Whether secret intermediates are wiped with `zeroize`.
+    pub zeroize: bool,
+}
+
+/// Describes the security level and backend features of this build.
+///
+/// Every field is fixed at compile time from the enabled features.
+///
+/// # Example
+/// ```
+/// use kyberlib::build_info;
+/// let info = build_info();
+/// assert_eq!(info.level.k(), kyberlib::KYBER_SECURITY_PARAMETER);
+/// assert_eq!(info.mode_90s, kyberlib::KYBER_90S);
+/// ```
+pub const fn build_info() -> BuildInfo {
+    let level = match KYBER_SECURITY_PARAMETER {
+        2 => params::SecurityLevel::Kyber512,
+        4 => params::SecurityLevel::Kyber1024,
+        _ => params::SecurityLevel::Kyber768,
+    };
+    BuildInfo {
+        level,
+        mode_90s: KYBER_90S,
+        avx2: cfg!(all(target_arch = "x86_64", feature = "avx2")),
+        zeroize: cfg!(feature = "zeroize"),
+    }
+}
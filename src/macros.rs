@@ -26,6 +26,33 @@ macro_rules! kyberlib_assert {
     };
 }
 
+/// Returns `Err(err)` from the enclosing function if a given expression
+/// is false, instead of panicking like `kyberlib_assert!`.
+///
+/// Use it in functions returning `Result<_, KyberLibError>` where a
+/// failed check must be recoverable, e.g. in firmware without unwinding.
+///
+/// # Examples
+///
+/// ```
+/// use kyberlib::{kyberlib_ensure, KyberLibError};
+///
+/// fn check(len: usize) -> Result<(), KyberLibError> {
+///     kyberlib_ensure!(len == 32, KyberLibError::InvalidLength);
+///     Ok(())
+/// }
+/// assert_eq!(check(32), Ok(()));
+/// assert_eq!(check(31), Err(KyberLibError::InvalidLength));
+/// ```
+#[macro_export]
+macro_rules! kyberlib_ensure {
+    ($cond:expr, $err:expr $(,)?) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
 /// Returns the minimum of the given values.
 ///
 /// # Examples
@@ -122,6 +149,9 @@ macro_rules! kyberlib_decrypt_message {
 ///
 /// # Arguments
 ///
+/// * `uake` - Optional `Uake` that keeps the ephemeral keys for
+///   `kyberlib_uake_client_confirm!()`. Without it a fresh `Uake` is used
+///   and discarded.
 /// * `pubkey` - Input public key (an already allocated array of CRYPTO_PUBLICKEYBYTES bytes).
 /// * `rng` - Random number generator implementing RngCore + CryptoRng.
 ///
@@ -131,6 +161,9 @@ macro_rules! kyberlib_decrypt_message {
 #[macro_export]
 #[doc = "Macro to initiate a Unilaterally Authenticated Key Exchange."]
 macro_rules! kyberlib_uake_client_init {
+    ($uake:expr, $pubkey:expr, $rng:expr) => {
+        $uake.client_init($pubkey, $rng)
+    };
     ($pubkey:expr, $rng:expr) => {
         kyberlib::kex::Uake::new().client_init($pubkey, $rng)
     };
@@ -140,6 +173,8 @@ macro_rules! kyberlib_uake_client_init {
 ///
 /// # Arguments
 ///
+/// * `uake` - Optional `Uake` that keeps the server's shared secret.
+///   Without it a fresh `Uake` is used and discarded.
 /// * `send_a` - The bytes received from the `kyberlib_uake_client_init()` request.
 /// * `secretkey` - The secret key (an already allocated array of CRYPTO_SECRETKEYBYTES bytes).
 /// * `rng` - Random number generator implementing RngCore + CryptoRng.
@@ -150,6 +185,9 @@ macro_rules! kyberlib_uake_client_init {
 #[macro_export]
 #[doc = "Macro to handle the output of a Unilaterally Authenticated Key Exchange."]
 macro_rules! kyberlib_uake_server_receive {
+    ($uake:expr, $send_a:expr, $secretkey:expr, $rng:expr) => {
+        $uake.server_receive($send_a, $secretkey, $rng)
+    };
     ($send_a:expr, $secretkey:expr, $rng:expr) => {
         kyberlib::kex::Uake::new()
             .server_receive($send_a, $secretkey, $rng)
@@ -161,16 +199,23 @@ macro_rules! kyberlib_uake_server_receive {
 ///
 /// # Arguments
 ///
+/// * `uake` - The `Uake` passed to `kyberlib_uake_client_init!()`, which
+///   holds the ephemeral keys.
 /// * `send_b` - The bytes received from the `kyberlib_uake_server_receive()` request.
 ///
 /// # Returns
 ///
 /// Nothing (the shared secret is stored in the `Uake` struct).
+///
+/// # Errors
+///
+/// Returns `KyberLibError::HandshakeOutOfOrder` if `uake` did not
+/// initiate the exchange.
 #[macro_export]
 #[doc = "Macro to decapsulate and authenticate the shared secret from a Unilaterally Authenticated Key Exchange."]
 macro_rules! kyberlib_uake_client_confirm {
-    ($send_b:expr) => {
-        kyberlib::kex::Uake::new().client_confirm($send_b)
+    ($uake:expr, $send_b:expr) => {
+        $uake.client_confirm($send_b)
     };
 }
 
@@ -178,6 +223,9 @@ macro_rules! kyberlib_uake_client_confirm {
 ///
 /// # Arguments
 ///
+/// * `ake` - Optional `Ake` that keeps the ephemeral keys for
+///   `kyberlib_ake_client_confirm!()`. Without it a fresh `Ake` is used
+///   and discarded.
 /// * `pubkey` - Input public key (an already allocated array of CRYPTO_PUBLICKEYBYTES bytes).
 /// * `rng` - Random number generator implementing RngCore + CryptoRng.
 ///
@@ -187,6 +235,9 @@ macro_rules! kyberlib_uake_client_confirm {
 #[macro_export]
 #[doc = "Macro to initiate a Mutually Authenticated Key Exchange."]
 macro_rules! kyberlib_ake_client_init {
+    ($ake:expr, $pubkey:expr, $rng:expr) => {
+        $ake.client_init($pubkey, $rng)
+    };
     ($pubkey:expr, $rng:expr) => {
         kyberlib::kex::Ake::new().client_init($pubkey, $rng)
     };
@@ -196,6 +247,8 @@ macro_rules! kyberlib_ake_client_init {
 ///
 /// # Arguments
 ///
+/// * `ake` - Optional `Ake` that keeps the server's shared secret.
+///   Without it a fresh `Ake` is used and discarded.
 /// * `ake_send_a` - The bytes received from the `kyberlib_ake_client_init()` request.
 /// * `pubkey` - The public key (an already allocated array of CRYPTO_PUBLICKEYBYTES bytes).
 /// * `secretkey` - The secret key (an already allocated array of CRYPTO_SECRETKEYBYTES bytes).
@@ -207,6 +260,9 @@ macro_rules! kyberlib_ake_client_init {
 #[macro_export]
 #[doc = "Macro to handle the output of a Mutually Authenticated Key Exchange."]
 macro_rules! kyberlib_ake_server_receive {
+    ($ake:expr, $ake_send_a:expr, $pubkey:expr, $secretkey:expr, $rng:expr) => {
+        $ake.server_receive($ake_send_a, $pubkey, $secretkey, $rng)
+    };
     ($ake_send_a:expr, $pubkey:expr, $secretkey:expr, $rng:expr) => {
         kyberlib::kex::Ake::new().server_receive(
             $ake_send_a,
@@ -222,16 +278,23 @@ macro_rules! kyberlib_ake_server_receive {
 ///
 /// # Arguments
 ///
+/// * `ake` - The `Ake` passed to `kyberlib_ake_client_init!()`, which
+///   holds the ephemeral keys.
 /// * `send_b` - The bytes received from the `kyberlib_ake_server_receive()` request.
 /// * `secretkey` - The secret key (an already allocated array of CRYPTO_SECRETKEYBYTES bytes).
 ///
 /// # Returns
 ///
 /// Nothing (the shared secret is stored in the `Ake` struct).
+///
+/// # Errors
+///
+/// Returns `KyberLibError::HandshakeOutOfOrder` if `ake` did not
+/// initiate the exchange.
 #[macro_export]
 #[doc = "Macro to decapsulate and authenticate the shared secret from a Mutually Authenticated Key Exchange."]
 macro_rules! kyberlib_ake_client_confirm {
-    ($send_b:expr, $secretkey:expr) => {
-        kyberlib::kex::Ake::new().client_confirm($send_b, $secretkey)
+    ($ake:expr, $send_b:expr, $secretkey:expr) => {
+        $ake.client_confirm($send_b, $secretkey)
     };
 }
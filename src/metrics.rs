@@ -0,0 +1,76 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{convert::TryFrom, sync::RwLock, time::Instant};
+
+/// The operations reported to the metrics hook.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Operation {
+    /// Key pair generation with `keypair`.
+    Keypair,
+    /// Encapsulation with `encapsulate` or one of its variants.
+    Encapsulate,
+    /// Decapsulation with `decapsulate` or one of its variants.
+    Decapsulate,
+}
+
+/// Callback receiving an operation and its duration in nanoseconds.
+pub type MetricsHook = fn(op: Operation, nanos: u64);
+
+static HOOK: RwLock<Option<MetricsHook>> = RwLock::new(None);
+
+/// Installs a callback invoked after each key generation, encapsulation
+/// and decapsulation call, replacing any previous hook.
+///
+/// No hook is installed by default, in which case timing is a no-op.
+/// The hook runs on the calling thread after the operation finishes,
+/// whether or not it succeeded.
+///
+/// # Example
+/// ```
+/// use kyberlib::metrics::{set_metrics_hook, Operation};
+///
+/// fn log(op: Operation, nanos: u64) {
+///     println!("{:?} took {} ns", op, nanos);
+/// }
+/// set_metrics_hook(log);
+/// ```
+pub fn set_metrics_hook(hook: MetricsHook) {
+    if let Ok(mut guard) = HOOK.write() {
+        *guard = Some(hook);
+    }
+}
+
+/// Removes the installed metrics hook, if any.
+pub fn clear_metrics_hook() {
+    if let Ok(mut guard) = HOOK.write() {
+        *guard = None;
+    }
+}
+
+// Times an operation and reports it to the hook when dropped
+#[derive(Debug)]
+pub(crate) struct Timer {
+    op: Operation,
+    start: Instant,
+}
+
+impl Timer {
+    pub(crate) fn start(op: Operation) -> Self {
+        Timer {
+            op,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let hook = HOOK.read().ok().and_then(|guard| *guard);
+        if let Some(hook) = hook {
+            let nanos = u64::try_from(self.start.elapsed().as_nanos())
+                .unwrap_or(u64::MAX);
+            hook(self.op, nanos);
+        }
+    }
+}
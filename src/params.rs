@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 // Constants and parameters used in the Kyber cryptographic scheme.
+// The `*_stack_estimate` functions add up buffer sizes and a fixed,
+// unmeasured allowance, so they are estimates rather than bounds.
 
 /// Enables or disables 90's mode in the Kyber cryptographic scheme.
 ///
@@ -26,6 +28,10 @@ pub const KYBER_ETA2: usize = 2;
 /// Size of the hashes and seeds
 pub const KYBER_SYM_BYTES: usize = 32;
 
+/// Size of the seed taken by `derive`: the 32-byte key generation seed
+/// `d` followed by the 32-byte implicit-rejection value `z`.
+pub const KYBER_DERIVE_SEED_BYTES: usize = 2 * KYBER_SYM_BYTES;
+
 /// The parameter N, representing the degree of the polynomial used in Kyber.
 ///
 /// - This constant is a fundamental part of the scheme's structure.
@@ -126,3 +132,186 @@ pub const KYBER_PUBLIC_KEY_BYTES: usize = KYBER_INDCPA_PUBLIC_KEY_BYTES;
 ///
 /// - This size dictates the memory requirements for storing and transmitting encrypted data.
 pub const KYBER_CIPHERTEXT_BYTES: usize = KYBER_INDCPA_BYTES;
+
+/// A Kyber parameter set, independent of the one compiled in.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SecurityLevel {
+    /// Kyber512, k = 2.
+    Kyber512,
+    /// Kyber768, k = 3.
+    Kyber768,
+    /// Kyber1024, k = 4.
+    Kyber1024,
+}
+
+impl SecurityLevel {
+    /// Returns the module rank k of this level.
+    pub const fn k(self) -> usize {
+        match self {
+            SecurityLevel::Kyber512 => 2,
+            SecurityLevel::Kyber768 => 3,
+            SecurityLevel::Kyber1024 => 4,
+        }
+    }
+
+    /// Identifies the level of a public key from its length.
+    ///
+    /// Returns `None` if `len` is not one of the three canonical
+    /// public key sizes.
+    ///
+    /// # Example
+    /// ```
+    /// use kyberlib::params::SecurityLevel;
+    /// assert_eq!(
+    ///     SecurityLevel::from_public_key_len(1184),
+    ///     Some(SecurityLevel::Kyber768)
+    /// );
+    /// ```
+    pub const fn from_public_key_len(len: usize) -> Option<Self> {
+        const KYBER512: usize =
+            sizes_for(SecurityLevel::Kyber512).public_key;
+        const KYBER768: usize =
+            sizes_for(SecurityLevel::Kyber768).public_key;
+        const KYBER1024: usize =
+            sizes_for(SecurityLevel::Kyber1024).public_key;
+        match len {
+            KYBER512 => Some(SecurityLevel::Kyber512),
+            KYBER768 => Some(SecurityLevel::Kyber768),
+            KYBER1024 => Some(SecurityLevel::Kyber1024),
+            _ => None,
+        }
+    }
+}
+
+/// Byte sizes of the keys, ciphertext and shared secret at one level.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct KyberSizes {
+    /// Size of a public key.
+    pub public_key: usize,
+    /// Size of a secret key.
+    pub secret_key: usize,
+    /// Size of a ciphertext.
+    pub ciphertext: usize,
+    /// Size of a shared secret.
+    pub shared_secret: usize,
+}
+
+/// Returns the message sizes for any security level.
+///
+/// The sizes are computed from the Kyber formulas rather than the
+/// compiled constants, so a relay built for one level can still
+/// validate messages of the others.
+///
+/// # Example
+/// ```
+/// use kyberlib::params::{sizes_for, SecurityLevel};
+/// assert_eq!(sizes_for(SecurityLevel::Kyber1024).ciphertext, 1568);
+/// ```
+pub const fn sizes_for(level: SecurityLevel) -> KyberSizes {
+    let k = level.k();
+    let polyvec_bytes = k * KYBER_POLY_BYTES;
+    let public_key = polyvec_bytes + KYBER_SYM_BYTES;
+    // (du, dv) is (11, 5) for Kyber1024 and (10, 4) otherwise
+    let ciphertext = match level {
+        SecurityLevel::Kyber1024 => k * 352 + 160,
+        _ => k * 320 + 128,
+    };
+    KyberSizes {
+        public_key,
+        secret_key: polyvec_bytes + public_key + 2 * KYBER_SYM_BYTES,
+        ciphertext,
+        shared_secret: KYBER_SHARED_SECRET_BYTES,
+    }
+}
+
+/// Returns the public key size for any security level.
+///
+/// Shorthand for `sizes_for(level).public_key`.
+///
+/// # Example
+/// ```
+/// use kyberlib::params::{public_key_len_for, SecurityLevel};
+/// assert_eq!(public_key_len_for(SecurityLevel::Kyber512), 800);
+/// ```
+pub const fn public_key_len_for(level: SecurityLevel) -> usize {
+    sizes_for(level).public_key
+}
+
+/// Returns the ciphertext size for any security level.
+///
+/// Shorthand for `sizes_for(level).ciphertext`.
+///
+/// # Example
+/// ```
+/// use kyberlib::params::{ciphertext_len_for, SecurityLevel};
+/// assert_eq!(ciphertext_len_for(SecurityLevel::Kyber768), 1088);
+/// ```
+pub const fn ciphertext_len_for(level: SecurityLevel) -> usize {
+    sizes_for(level).ciphertext
+}
+
+/// Returns the shared secret size for any security level.
+///
+/// The shared secret is 32 bytes at every level; the function exists so
+/// code that negotiates the level can size all three buffers the same
+/// way.
+///
+/// # Example
+/// ```
+/// use kyberlib::params::{shared_secret_len_for, SecurityLevel};
+/// assert_eq!(shared_secret_len_for(SecurityLevel::Kyber1024), 32);
+/// ```
+pub const fn shared_secret_len_for(level: SecurityLevel) -> usize {
+    sizes_for(level).shared_secret
+}
+
+// In-memory size of one polynomial: `KYBER_N` i16 coefficients
+const POLY_STACK_BYTES: usize = KYBER_N * 2;
+
+// Size of a polynomial vector held on the stack
+const POLYVEC_STACK_BYTES: usize =
+    KYBER_SECURITY_PARAMETER * POLY_STACK_BYTES;
+
+// Allowance for Keccak or AES states, matrix sampling buffers and call
+// frames, which do not depend on the security level
+const STACK_OVERHEAD_BYTES: usize = 4096;
+
+/// Returns an estimate, in bytes, of the stack used by
+/// key generation at the compiled security level.
+///
+/// - Covers the k x k public matrix, the secret, error and public
+///   polynomial vectors, the encoded key buffers and the seeds.
+pub const fn keygen_stack_estimate() -> usize {
+    KYBER_SECURITY_PARAMETER * POLYVEC_STACK_BYTES
+        + 4 * POLYVEC_STACK_BYTES
+        + KYBER_PUBLIC_KEY_BYTES
+        + KYBER_SECRET_KEY_BYTES
+        + 4 * KYBER_SYM_BYTES
+        + STACK_OVERHEAD_BYTES
+}
+
+/// Returns an estimate, in bytes, of the stack used by
+/// encapsulation at the compiled security level.
+///
+/// - Covers the transposed matrix, three polynomial vectors, three
+///   polynomials, the ciphertext and the hash buffers.
+pub const fn encapsulate_stack_estimate() -> usize {
+    KYBER_SECURITY_PARAMETER * POLYVEC_STACK_BYTES
+        + 3 * POLYVEC_STACK_BYTES
+        + 3 * POLY_STACK_BYTES
+        + KYBER_CIPHERTEXT_BYTES
+        + 6 * KYBER_SYM_BYTES
+        + STACK_OVERHEAD_BYTES
+}
+
+/// Returns an estimate, in bytes, of the stack used by
+/// decapsulation at the compiled security level.
+///
+/// - Covers the decryption vectors plus a full re-encryption and the
+///   ciphertext kept for the constant-time comparison.
+pub const fn decapsulate_stack_estimate() -> usize {
+    encapsulate_stack_estimate()
+        + 2 * POLYVEC_STACK_BYTES
+        + 2 * POLY_STACK_BYTES
+        + KYBER_CIPHERTEXT_BYTES
+}
@@ -0,0 +1,32 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The common KyberLib surface in one import.
+//!
+//! `use kyberlib::*;` brings in every public item, including the
+//! modules and helpers most applications never touch. The prelude
+//! re-exports only the key encapsulation functions, the key exchange
+//! structs, the error type and the size constants.
+//!
+//! # Example
+//! ```
+//! use kyberlib::prelude::*;
+//! # fn main() -> Result<(), KyberLibError> {
+//! let mut rng = rand::thread_rng();
+//! let keys: Keypair = keypair(&mut rng)?;
+//! let (ct, ss1) = encapsulate(&keys.public, &mut rng)?;
+//! let ss2 = decapsulate(&ct, &keys.secret)?;
+//! assert_eq!(ss1, ss2);
+//! assert_eq!(ct.len(), KYBER_CIPHERTEXT_BYTES);
+//! # Ok(()) }
+//! ```
+
+pub use crate::{
+    api::{decapsulate, encapsulate, keypair, Keypair},
+    error::KyberLibError,
+    kex::{Ake, Uake},
+    params::{
+        KYBER_CIPHERTEXT_BYTES, KYBER_PUBLIC_KEY_BYTES,
+        KYBER_SECRET_KEY_BYTES, KYBER_SHARED_SECRET_BYTES,
+    },
+};
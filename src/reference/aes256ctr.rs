@@ -48,10 +48,7 @@ impl Default for Aes256CtrCtx {
 }
 
 fn br_dec32le(src: &[u8]) -> u32 {
-    src[0] as u32
-        | (src[1] as u32) << 8
-        | (src[2] as u32) << 16
-        | (src[3] as u32) << 24
+    u32::from_le_bytes([src[0], src[1], src[2], src[3]])
 }
 
 fn br_range_dec32le(v: &mut [u32], mut num: usize, src: &[u8]) {
@@ -71,10 +68,7 @@ fn br_swap32(mut x: u32) -> u32 {
 }
 
 fn br_enc32le(dst: &mut [u8], x: u32) {
-    dst[0] = x as u8;
-    dst[1] = (x >> 8) as u8;
-    dst[2] = (x >> 16) as u8;
-    dst[3] = (x >> 24) as u8;
+    dst[..4].copy_from_slice(&x.to_le_bytes());
 }
 
 fn br_range_enc32le(dst: &mut [u8], v: &[u32], mut num: usize) {
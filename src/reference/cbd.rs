@@ -10,14 +10,10 @@ use crate::poly::Poly;
 ///
 /// Returns 32-bit unsigned integer loaded from x
 fn load32_littleendian(x: &[u8]) -> u32 {
-    let mut r = x[0] as u32;
-    r |= (x[1] as u32) << 8;
-    r |= (x[2] as u32) << 16;
-    r |= (x[3] as u32) << 24;
-    r
+    u32::from_le_bytes([x[0], x[1], x[2], x[3]])
 }
 
-/// Name:  load32_littleendian
+/// Name:  load24_littleendian
 ///
 /// Description: load 3 bytes into a 32-bit integer
 ///  in little-endian order
@@ -27,10 +23,7 @@ fn load32_littleendian(x: &[u8]) -> u32 {
 ///
 /// Returns 32-bit unsigned integer loaded from x
 fn load24_littleendian(x: &[u8]) -> u32 {
-    let mut r = x[0] as u32;
-    r |= (x[1] as u32) << 8;
-    r |= (x[2] as u32) << 16;
-    r
+    u32::from_le_bytes([x[0], x[1], x[2], 0])
 }
 
 /// Name:  cbd2
@@ -20,11 +20,9 @@ fn rol(a: u64, offset: u64) -> u64 {
 ///
 /// Returns the loaded 64-bit unsigned integer
 pub(crate) fn load64(x: &[u8]) -> u64 {
-    let mut r = 0u64;
-    for i in 0..8 {
-        r |= (x[i] as u64) << (8 * i);
-    }
-    r
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&x[..8]);
+    u64::from_le_bytes(bytes)
 }
 
 /// Name:  store64
@@ -33,10 +31,10 @@ pub(crate) fn load64(x: &[u8]) -> u64 {
 ///
 /// Arguments:   - [u8] x: the output byte array
 ///  - u64 u: input 64-bit unsigned integer
-pub(crate) fn store64(x: &mut [u8], mut u: u64) {
-    for i in x.iter_mut().take(8) {
-        *i = u as u8;
-        u >>= 8;
+pub(crate) fn store64(x: &mut [u8], u: u64) {
+    // Shorter outputs receive the low-order bytes only
+    for (dst, src) in x.iter_mut().zip(u.to_le_bytes().iter()) {
+        *dst = *src;
     }
 }
 
@@ -68,12 +66,27 @@ const KECCAKF_ROUNDCONSTANTS: [u64; NROUNDS] = [
     0x8000000080008008,
 ];
 
+/// First round of the 12-round Keccak-p[1600] used by TurboSHAKE
+#[cfg(feature = "turboshake")]
+const TURBOSHAKE_FIRST_ROUND: usize = NROUNDS - 12;
+
 /// Name:  KeccakF1600_StatePermute
 ///
 /// Description: The Keccak F1600 Permutation
 ///
 /// Arguments:   - u64 * state: in/output Keccak state
 pub(crate) fn keccakf1600_statepermute(state: &mut [u64]) {
+    keccakp1600_statepermute(state, 0);
+}
+
+/// Name:  KeccakP1600_StatePermute
+///
+/// Description: The Keccak-p[1600] permutation running the rounds
+///  `first_round..NROUNDS`; `first_round` must be even.
+///
+/// Arguments:   - u64 * state: in/output Keccak state
+///  - usize first_round: index of the first round to apply
+fn keccakp1600_statepermute(state: &mut [u64], first_round: usize) {
     //copyFromState(A, state)
     let mut aba = state[0];
     let mut abe = state[1];
@@ -101,7 +114,7 @@ pub(crate) fn keccakf1600_statepermute(state: &mut [u64]) {
     let mut aso = state[23];
     let mut asu = state[24];
 
-    for round in (0..NROUNDS).step_by(2) {
+    for round in (first_round..NROUNDS).step_by(2) {
         // prepareTheta
         let mut bca = aba ^ aga ^ aka ^ ama ^ asa;
         let mut bce = abe ^ age ^ ake ^ ame ^ ase;
@@ -331,14 +344,25 @@ pub(crate) fn keccakf1600_statepermute(state: &mut [u64]) {
 ///  - u64 *s:   in/output Keccak state
 ///  - usize r:  rate in bytes (e.g., 168 for SHAKE128)
 pub(crate) fn keccak_squeezeblocks(
+    h: &mut [u8],
+    nblocks: usize,
+    s: &mut [u64],
+    r: usize,
+) {
+    keccak_squeezeblocks_rounds(h, nblocks, s, r, 0);
+}
+
+// keccak_squeezeblocks over the rounds `first_round..NROUNDS`
+fn keccak_squeezeblocks_rounds(
     h: &mut [u8],
     mut nblocks: usize,
     s: &mut [u64],
     r: usize,
+    first_round: usize,
 ) {
     let mut idx = 0usize;
     while nblocks > 0 {
-        keccakf1600_statepermute(s);
+        keccakp1600_statepermute(s, first_round);
         for i in 0..r / 8 {
             store64(&mut h[idx + 8 * i..], s[i])
         }
@@ -389,6 +413,29 @@ pub(crate) fn shake256(
     shake256_squeeze(&mut out[idx..], outlen, &mut state);
 }
 
+// SHAKE256 of the concatenation of `parts`, absorbed byte by byte so the
+// inputs need not be copied into one buffer
+pub(crate) fn shake256_concat(
+    out: &mut [u8],
+    outlen: usize,
+    parts: &[&[u8]],
+) {
+    let mut state = KeccakState::new();
+    let mut pos = 0;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        state.s[pos / 8] ^= u64::from(byte) << (8 * (pos % 8));
+        pos += 1;
+        if pos == SHAKE256_RATE {
+            keccakf1600_statepermute(&mut state.s);
+            pos = 0;
+        }
+    }
+    state.s[pos / 8] ^= 0x1Fu64 << (8 * (pos % 8));
+    state.s[(SHAKE256_RATE - 1) / 8] ^= 1u64 << 63;
+    state.pos = SHAKE256_RATE;
+    shake256_squeeze(out, outlen, &mut state);
+}
+
 /// Name:  sha3_256
 ///
 /// Description: SHA3-256 with non-incremental API
@@ -445,11 +492,23 @@ fn keccak_finalize(s: &mut [u64], pos: usize, r: usize, p: u8) {
 ///  - u64 mlen: length of input in bytes
 ///  - [u8]  p:   domain-separation byte for different Keccak-derived functions
 pub(crate) fn keccak_absorb_once(
+    s: &mut [u64],
+    r: usize,
+    input: &[u8],
+    inlen: usize,
+    p: u8,
+) {
+    keccak_absorb_once_rounds(s, r, input, inlen, p, 0);
+}
+
+// keccak_absorb_once over the rounds `first_round..NROUNDS`
+fn keccak_absorb_once_rounds(
     s: &mut [u64],
     r: usize,
     input: &[u8],
     mut inlen: usize,
     p: u8,
+    first_round: usize,
 ) {
     // Zero State
     s.fill(0);
@@ -461,7 +520,7 @@ pub(crate) fn keccak_absorb_once(
         }
         idx += r;
         inlen -= r;
-        keccakf1600_statepermute(s);
+        keccakp1600_statepermute(s, first_round);
     }
 
     for i in 0..inlen {
@@ -484,25 +543,35 @@ pub(crate) fn keccak_absorb_once(
 ///  - usize r:  rate in bytes (e.g., 168 for SHAKE128)
 /// Returns new position pos in current block
 pub(crate) fn keccak_squeeze(
+    out: &mut [u8],
+    outlen: usize,
+    s: &mut [u64],
+    pos: usize,
+    r: usize,
+) -> usize {
+    keccak_squeeze_rounds(out, outlen, s, pos, r, 0)
+}
+
+// keccak_squeeze over the rounds `first_round..NROUNDS`
+fn keccak_squeeze_rounds(
     out: &mut [u8],
     mut outlen: usize,
     s: &mut [u64],
     mut pos: usize,
     r: usize,
+    first_round: usize,
 ) -> usize {
     let mut idx = 0;
     while outlen > 0 {
         if pos == r {
-            keccakf1600_statepermute(s);
+            keccakp1600_statepermute(s, first_round);
             pos = 0
         }
         let mut i = pos;
-        let mut w = i / 8;
         while i < r && i < pos + outlen {
-            store64(&mut out[idx..], s[w]);
-            i += 8;
-            w += 1;
-            idx += 8;
+            out[idx] = (s[i / 8] >> (8 * (i % 8))) as u8;
+            i += 1;
+            idx += 1;
         }
         outlen -= i - pos;
         pos = i;
@@ -607,7 +676,7 @@ fn shake256_squeezeblocks(
     keccak_squeezeblocks(out, nblocks, &mut state.s, SHAKE256_RATE);
 }
 
-fn shake128(
+pub(crate) fn shake128(
     out: &mut [u8],
     mut outlen: usize,
     input: &[u8],
@@ -622,3 +691,168 @@ fn shake128(
     idx += nblocks * SHAKE128_RATE;
     shake128_squeeze(&mut out[idx..], outlen, &mut state);
 }
+
+/// Name:  turboshake128_absorb_once
+///
+/// Description: Initialize, absorb into and finalize TurboSHAKE128 with
+///  domain separation byte 0x1F; non-incremental.
+///
+/// Arguments:   - keccak_state state: pointer to (uninitialized) output Keccak state
+///  - const [u8] in: input to be absorbed into s
+///  - usize inlen: length of input in bytes
+#[cfg(feature = "turboshake")]
+pub(crate) fn turboshake128_absorb_once(
+    state: &mut KeccakState,
+    input: &[u8],
+    inlen: usize,
+) {
+    keccak_absorb_once_rounds(
+        &mut state.s,
+        SHAKE128_RATE,
+        input,
+        inlen,
+        0x1F,
+        TURBOSHAKE_FIRST_ROUND,
+    );
+    state.pos = SHAKE128_RATE;
+}
+
+/// Name:  turboshake128_squeezeblocks
+///
+/// Description: Squeeze step of TurboSHAKE128. Squeezes full blocks of
+///  SHAKE128_RATE bytes each.
+///
+/// Arguments:   - [u8] out: pointer to output blocks
+///  - u64 nblocks: number of blocks to be squeezed (written to output)
+///  - KeccakState state: pointer to input/output Keccak state
+#[cfg(feature = "turboshake")]
+pub(crate) fn turboshake128_squeezeblocks(
+    out: &mut [u8],
+    nblocks: usize,
+    state: &mut KeccakState,
+) {
+    keccak_squeezeblocks_rounds(
+        out,
+        nblocks,
+        &mut state.s,
+        SHAKE128_RATE,
+        TURBOSHAKE_FIRST_ROUND,
+    );
+}
+
+/// Name:  turboshake256
+///
+/// Description: TurboSHAKE256 with domain separation byte 0x1F and
+///  non-incremental API
+///
+/// Arguments:   - [u8] output:  output
+///  - usize outlen:  requested output length in bytes
+///  - [u8] input: input
+///  - usize inlen:   length of input in bytes
+#[cfg(feature = "turboshake")]
+pub(crate) fn turboshake256(
+    out: &mut [u8],
+    outlen: usize,
+    input: &[u8],
+    inlen: usize,
+) {
+    let mut state = KeccakState::new();
+    keccak_absorb_once_rounds(
+        &mut state.s,
+        SHAKE256_RATE,
+        input,
+        inlen,
+        0x1F,
+        TURBOSHAKE_FIRST_ROUND,
+    );
+    let _ = keccak_squeeze_rounds(
+        out,
+        outlen,
+        &mut state.s,
+        SHAKE256_RATE,
+        SHAKE256_RATE,
+        TURBOSHAKE_FIRST_ROUND,
+    );
+}
+
+#[cfg(all(test, feature = "turboshake"))]
+mod tests {
+    use super::*;
+
+    // TurboSHAKE test vectors from RFC 9861, section 5, with D = 0x1F.
+    // ptn(n) is the repeating pattern 00 01 .. FA truncated to n bytes.
+    fn ptn(n: usize) -> [u8; 289] {
+        let mut out = [0u8; 289];
+        for (i, byte) in out.iter_mut().enumerate().take(n) {
+            *byte = (i % 0xFB) as u8;
+        }
+        out
+    }
+
+    fn decode(hex: &str) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte =
+                u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    const TURBOSHAKE128: [(usize, &str); 3] = [
+        (
+            0,
+            "1E415F1C5983AFF2169217277D17BB538CD945A397DDEC541F1CE41AF2C1B74C\
+             3E8CCAE2A4DAE56C84A04C2385C03C15E8193BDF58737363321691C05462C8DF",
+        ),
+        (
+            17,
+            "9C97D036A3BAC819DB70EDE0CA554EC6E4C2A1A4FFBFD9EC269CA6A111161233\
+             AC6570FDC9F2EA31E1AC6D1D65F7D04662E4CF67BBBF7D89F8B4ECE2F23A7E57",
+        ),
+        (
+            289,
+            "96C77C279E0126F7FC07C9B07F5CDAE1E0BE60BDBE10620040E75D7223A624D2\
+             A7880F91D4D6B5732674FA038BB6A46A8CB87127DEB0FC7D346A004ED0A17745",
+        ),
+    ];
+
+    const TURBOSHAKE256: [(usize, &str); 3] = [
+        (
+            0,
+            "367A329DAFEA871C7802EC67F905AE13C57695DC2C6663C61035F59A18F8E7DB\
+             11EDC0E12E91EA60EB6B32DF06DD7F002FBAFABB6E13EC1CC20D995547600DB0",
+        ),
+        (
+            17,
+            "B3BAB0300E6A191FBE6137939835923578794EA54843F5011090FA2F3780A9E5\
+             CB22C59D78B40A0FBFF9E672C0FBE0970BD2C845091C6044D687054DA5D8E9C7",
+        ),
+        (
+            289,
+            "66B810DB8E90780424C0847372FDC95710882FDE31C6DF75BEB9D4CD9305CFCA\
+             E35E7B83E8B7E6EB4B78605880116316FE2C078A09B94AD7B8213C0A738B65C0",
+        ),
+    ];
+
+    #[test]
+    fn turboshake128_rfc9861_vectors() {
+        for (len, expected) in TURBOSHAKE128 {
+            let input = ptn(len);
+            let mut state = KeccakState::new();
+            let mut out = [0u8; SHAKE128_RATE];
+            turboshake128_absorb_once(&mut state, &input[..len], len);
+            turboshake128_squeezeblocks(&mut out, 1, &mut state);
+            assert_eq!(out[..64], decode(expected), "ptn({})", len);
+        }
+    }
+
+    #[test]
+    fn turboshake256_rfc9861_vectors() {
+        for (len, expected) in TURBOSHAKE256 {
+            let input = ptn(len);
+            let mut out = [0u8; 64];
+            turboshake256(&mut out, 64, &input[..len], len);
+            assert_eq!(out, decode(expected), "ptn({})", len);
+        }
+    }
+}
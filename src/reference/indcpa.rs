@@ -125,6 +125,9 @@ fn rej_uniform(
             ctr += 1;
         }
     }
+    #[cfg(feature = "debug-internals")]
+    crate::hazmat::REJECTION_COUNT
+        .with(|count| count.set(count.get() + pos / 3));
     ctr
 }
 
@@ -156,6 +159,8 @@ fn gen_matrix(a: &mut [Polyvec], seed: &[u8], transposed: bool) {
     let mut buflen: usize;
     let mut off: usize;
     let mut state = XofState::new();
+    #[cfg(feature = "debug-internals")]
+    crate::hazmat::REJECTION_COUNT.with(|count| count.set(0));
 
     #[allow(clippy::needless_range_loop)]
     for i in 0..KYBER_SECURITY_PARAMETER {
@@ -221,7 +226,7 @@ where
         randombytes(&mut randbuf, KYBER_SYM_BYTES, _rng)?;
     }
 
-    hash_g(&mut buf, &randbuf, KYBER_SYM_BYTES);
+    hash_g_unchecked(&mut buf, &randbuf, KYBER_SYM_BYTES);
 
     let (publicseed, noiseseed) = buf.split_at(KYBER_SYM_BYTES);
     gen_a(&mut a, publicseed);
@@ -270,6 +275,45 @@ pub(crate) fn indcpa_enc(
     coins: &[u8],
 ) {
     let mut at = [Polyvec::new(); KYBER_SECURITY_PARAMETER];
+    indcpa_expand_at(&mut at, pk);
+    indcpa_enc_prepared(c, m, pk, &at, coins);
+}
+
+/// The transposed matrix A expanded from a public key's seed.
+pub(crate) type PublicMatrix = [Polyvec; KYBER_SECURITY_PARAMETER];
+
+/// Name:  indcpa_expand_at
+///
+/// Description: Expands the transposed matrix A from the seed of a
+///  packed public key, for reuse across encryptions.
+///
+/// Arguments:
+///  - Polyvec at:    output matrix (KYBER_SECURITY_PARAMETER rows)
+///  - const [u8] pk: input public key (length KYBER_INDCPA_PUBLIC_KEY_BYTES)
+pub(crate) fn indcpa_expand_at(at: &mut [Polyvec], pk: &[u8]) {
+    gen_at(at, &pk[KYBER_POLYVEC_BYTES..][..KYBER_SYM_BYTES]);
+}
+
+/// Name:  indcpa_enc_prepared
+///
+/// Description: Encryption function of the CPA-secure
+///  public-key encryption scheme using a matrix A already expanded
+///  by indcpa_expand_at from the same public key.
+///
+/// Arguments:
+///  - const [u8] c:    output ciphertext (length KYBER_INDCPA_BYTES)
+///  - const [u8] m:    input message (length KYBER_SYM_BYTES)
+///  - const [u8] pk:   input public key (length KYBER_INDCPA_PUBLIC_KEY_BYTES)
+///  - const Polyvec at: input transposed matrix A of pk
+///  - const [u8] coin: input random coins used as seed (length KYBER_SYM_BYTES)
+///    to deterministically generate all randomness
+pub(crate) fn indcpa_enc_prepared(
+    c: &mut [u8],
+    m: &[u8],
+    pk: &[u8],
+    at: &[Polyvec],
+    coins: &[u8],
+) {
     let (mut sp, mut pkpv, mut ep, mut b) = (
         Polyvec::new(),
         Polyvec::new(),
@@ -283,7 +327,6 @@ pub(crate) fn indcpa_enc(
 
     unpack_pk(&mut pkpv, &mut seed, pk);
     poly_frommsg(&mut k, m);
-    gen_at(&mut at, &seed);
 
     for i in 0..KYBER_SECURITY_PARAMETER {
         poly_getnoise_eta1(&mut sp.vec[i], coins, nonce);
@@ -326,6 +369,11 @@ pub(crate) fn indcpa_enc(
 ///  - const [u8] c:    input ciphertext (of length KYBER_INDCPA_BYTES)
 ///  - const [u8] sk:   input secret key (of length KYBER_INDCPA_SECRET_KEY_BYTES)
 pub(crate) fn indcpa_dec(m: &mut [u8], c: &[u8], sk: &[u8]) {
+    debug_assert_eq!(
+        c.len(),
+        KYBER_INDCPA_BYTES,
+        "indcpa_dec: ciphertext length mismatch"
+    );
     let (mut b, mut skpv) = (Polyvec::new(), Polyvec::new());
     let (mut v, mut mp) = (Poly::new(), Poly::new());
 
@@ -1,6 +1,10 @@
 pub(crate) mod aes256ctr;
 pub(crate) mod cbd;
 pub(crate) mod fips202;
+#[cfg(feature = "hazmat")]
+/// IND-CPA constants, exported with the `hazmat` feature.
+pub mod indcpa;
+#[cfg(not(feature = "hazmat"))]
 pub(crate) mod indcpa;
 pub(crate) mod ntt;
 pub(crate) mod poly;
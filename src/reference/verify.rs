@@ -8,6 +8,10 @@
 ///
 /// Returns 0 if the byte arrays are equal, 1 otherwise
 pub(crate) fn verify(a: &[u8], b: &[u8], len: usize) -> u8 {
+    debug_assert!(
+        a.len() == len && b.len() == len,
+        "verify: input length mismatch"
+    );
     let mut r = 0u64;
     for i in 0..len {
         r |= (a[i] ^ b[i]) as u64;
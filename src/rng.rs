@@ -2,6 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::KyberLibError;
+#[cfg(feature = "nist-drbg")]
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes256,
+};
 use rand_core::{CryptoRng, RngCore};
 
 /// Fills a buffer `x` with `len` bytes of random data.
@@ -18,8 +23,8 @@ use rand_core::{CryptoRng, RngCore};
 ///
 /// # Errors
 ///
-/// If the RNG fails to generate the required number of bytes, an error
-/// of type `KyberLibError::RandomBytesGeneration` is returned.
+/// If the RNG fails to generate the required number of bytes,
+/// `KyberLibError::RandomBytesGenerationSized` is returned carrying `len`.
 ///
 /// # Examples
 ///
@@ -48,6 +53,112 @@ where
         return Err(KyberLibError::InvalidLength);
     }
 
-    rng.try_fill_bytes(&mut x[..len])
-        .map_err(|_| KyberLibError::RandomBytesGeneration)
+    rng.try_fill_bytes(&mut x[..len]).map_err(|_| {
+        KyberLibError::RandomBytesGenerationSized { requested: len }
+    })
 }
+
+/// The AES-256 CTR_DRBG used by NIST to generate the PQC KAT vectors.
+///
+/// A port of `randombytes_init`/`randombytes` from the NIST `rng.c`,
+/// without derivation function or personalisation string. Seeded with
+/// the `seed` of a `PQCkemKAT` vector, `keypair` followed by
+/// `encapsulate` reproduce that vector's `pk`, `sk`, `ct` and `ss`,
+/// since each draw the library makes is a separate `randombytes` call
+/// in the same order as the reference implementation.
+///
+/// This is a known-answer test tool. Its output is fully determined by
+/// the seed, so never use it to generate real keys.
+///
+/// ### Example
+/// ```
+/// # use kyberlib::{rng::NistDrbg, *};
+/// # fn main() -> Result<(), KyberLibError> {
+/// let mut drbg = NistDrbg::new(&[0u8; 48]);
+/// let keys = keypair(&mut drbg)?;
+/// let mut again = NistDrbg::new(&[0u8; 48]);
+/// assert_eq!(keypair(&mut again)?, keys);
+/// # Ok(())}
+/// ```
+#[cfg(feature = "nist-drbg")]
+// Not Copy, so the DRBG state is never forked by accident
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug)]
+pub struct NistDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+}
+
+#[cfg(feature = "nist-drbg")]
+impl NistDrbg {
+    /// Seeds the DRBG, like `randombytes_init(seed, NULL, 256)`.
+    pub fn new(seed: &[u8; 48]) -> Self {
+        let mut drbg = NistDrbg {
+            key: [0u8; 32],
+            v: [0u8; 16],
+        };
+        drbg.update(Some(seed));
+        drbg
+    }
+
+    fn next_block(&mut self) -> [u8; 16] {
+        // Big-endian increment of V
+        for byte in self.v.iter_mut().rev() {
+            if *byte == 0xff {
+                *byte = 0;
+            } else {
+                *byte += 1;
+                break;
+            }
+        }
+        let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+        let mut block = GenericArray::clone_from_slice(&self.v);
+        cipher.encrypt_block(&mut block);
+        block.into()
+    }
+
+    fn update(&mut self, provided_data: Option<&[u8; 48]>) {
+        let mut temp = [0u8; 48];
+        for chunk in temp.chunks_mut(16) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        if let Some(data) = provided_data {
+            for (t, d) in temp.iter_mut().zip(data.iter()) {
+                *t ^= d;
+            }
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+}
+
+#[cfg(feature = "nist-drbg")]
+impl RngCore for NistDrbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    // Each call is one `randombytes(dest, dest.len())`
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(16) {
+            let block = self.next_block();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(None);
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nist-drbg")]
+impl CryptoRng for NistDrbg {}
@@ -0,0 +1,58 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The FIPS 202 primitives KyberLib uses internally, exposed so that
+//! protocols built around Kyber can reuse them instead of pulling in a
+//! second SHA3 implementation.
+
+use crate::fips202;
+
+/// Computes the SHA3-256 digest of `input`.
+///
+/// # Example
+/// ```
+/// # use kyberlib::sha3::sha3_256;
+/// let digest = sha3_256(b"abc");
+/// assert_eq!(digest[..4], [0x3a, 0x98, 0x5d, 0xa7]);
+/// ```
+pub fn sha3_256(input: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    fips202::sha3_256(&mut out, input, input.len());
+    out
+}
+
+/// Computes the SHA3-512 digest of `input`.
+///
+/// # Example
+/// ```
+/// # use kyberlib::sha3::sha3_512;
+/// let digest = sha3_512(b"abc");
+/// assert_eq!(digest[..4], [0xb7, 0x51, 0x85, 0x0b]);
+/// ```
+pub fn sha3_512(input: &[u8]) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    fips202::sha3_512(&mut out, input, input.len());
+    out
+}
+
+/// Fills `out` with SHAKE128 output for `input`.
+///
+/// # Arguments
+///
+/// * `out` - The output buffer, any length may be requested.
+/// * `input` - The data to absorb.
+pub fn shake128(out: &mut [u8], input: &[u8]) {
+    let outlen = out.len();
+    fips202::shake128(out, outlen, input, input.len());
+}
+
+/// Fills `out` with SHAKE256 output for `input`.
+///
+/// # Arguments
+///
+/// * `out` - The output buffer, any length may be requested.
+/// * `input` - The data to absorb.
+pub fn shake256(out: &mut [u8], input: &[u8]) {
+    let outlen = out.len();
+    fips202::shake256(out, outlen, input, input.len());
+}
@@ -5,6 +5,7 @@
 
 #[cfg(feature = "90s")]
 use crate::aes256ctr::*;
+use crate::error::KyberLibError;
 #[cfg(not(feature = "90s"))]
 use crate::{fips202::*, params::*};
 #[cfg(feature = "90s")]
@@ -33,6 +34,21 @@ pub const XOF_BLOCKBYTES: usize = AES256CTR_BLOCKBYTES;
 #[cfg(not(feature = "90s"))]
 pub const XOF_BLOCKBYTES: usize = SHAKE128_RATE;
 
+/// Returns the XOF block size in bytes for the active build.
+///
+/// 168 (the SHAKE128 rate) for SHAKE and TurboSHAKE builds, 64 for
+/// AES-256-CTR in 90s mode, so code buffering XOF output need not
+/// check features itself.
+///
+/// # Example
+/// ```
+/// const BLOCK: usize = kyberlib::symmetric::xof_block_bytes();
+/// let _buf = [0u8; BLOCK];
+/// ```
+pub const fn xof_block_bytes() -> usize {
+    XOF_BLOCKBYTES
+}
+
 /// Type alias for the XOF (Extendable Output Function) state.
 #[cfg(not(feature = "90s"))]
 pub type XofState = KeccakState;
@@ -66,30 +82,127 @@ impl KeccakState {
     }
 }
 
-/// Computes SHA3-256 hash
+/// Returns the name of the symmetric primitives compiled in.
+///
+/// `"shake"` for the standard SHA3/SHAKE instantiation,
+/// `"turboshake"` when the `turboshake` feature swaps the XOF and PRF
+/// for TurboSHAKE, and `"aes-sha2"` when the `90s` feature swaps in
+/// AES-256-CTR and SHA-2.
+pub fn symmetric_backend() -> &'static str {
+    if cfg!(feature = "90s") {
+        "aes-sha2"
+    } else if cfg!(feature = "turboshake") {
+        "turboshake"
+    } else {
+        "shake"
+    }
+}
+
+/// Returns the name of the AES-256-CTR implementation used by the PRF.
+///
+/// `"fixslice"` for RustCrypto's `aes` crate with the `90s-fixslice`
+/// feature (which itself switches to AES-NI at runtime on CPUs that
+/// have it), `"bitslice"` for the built-in port of Thomas Pornin's
+/// constant-time code, and `"none"` outside 90s mode. The XOF always
+/// uses the bitslice code.
+///
+/// # Example
+/// ```
+/// # use kyberlib::symmetric::aes_backend;
+/// if !kyberlib::KYBER_90S {
+///     assert_eq!(aes_backend(), "none");
+/// }
+/// ```
+pub fn aes_backend() -> &'static str {
+    if cfg!(feature = "90s-fixslice") {
+        "fixslice"
+    } else if cfg!(feature = "90s") {
+        "bitslice"
+    } else {
+        "none"
+    }
+}
+
+/// Computes the hash function H over `input[..inlen]`.
+///
+/// SHA3-256, or SHA2-256 in 90s mode.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidLength` if `inlen` exceeds
+/// `input.len()` or `out` is shorter than the 32-byte digest.
+pub fn hash_h(
+    out: &mut [u8],
+    input: &[u8],
+    inlen: usize,
+) -> Result<(), KyberLibError> {
+    if inlen > input.len() || out.len() < 32 {
+        return Err(KyberLibError::InvalidLength);
+    }
+    hash_h_unchecked(out, input, inlen);
+    Ok(())
+}
+
+/// Computes the hash function G over `input[..inlen]`.
+///
+/// SHA3-512, or SHA2-512 in 90s mode.
+///
+/// # Errors
+///
+/// Returns `KyberLibError::InvalidLength` if `inlen` exceeds
+/// `input.len()` or `out` is shorter than the 64-byte digest.
+pub fn hash_g(
+    out: &mut [u8],
+    input: &[u8],
+    inlen: usize,
+) -> Result<(), KyberLibError> {
+    if inlen > input.len() || out.len() < 64 {
+        return Err(KyberLibError::InvalidLength);
+    }
+    hash_g_unchecked(out, input, inlen);
+    Ok(())
+}
+
+// Computes SHA3-256 hash, for internal callers with known lengths
 #[cfg(not(feature = "90s"))]
-pub fn hash_h(out: &mut [u8], input: &[u8], inlen: usize) {
+pub(crate) fn hash_h_unchecked(
+    out: &mut [u8],
+    input: &[u8],
+    inlen: usize,
+) {
     sha3_256(out, input, inlen);
 }
 
-/// Computes SHA2-256 hash in 90s mode
+// Computes SHA2-256 hash in 90s mode
 #[cfg(feature = "90s")]
-pub fn hash_h(out: &mut [u8], input: &[u8], inlen: usize) {
+pub(crate) fn hash_h_unchecked(
+    out: &mut [u8],
+    input: &[u8],
+    inlen: usize,
+) {
     let mut hasher = Sha256::new();
     hasher.update(&input[..inlen]);
     let digest = hasher.finalize();
     out[..digest.len()].copy_from_slice(&digest);
 }
 
-/// Computes SHA3-512 hash
+// Computes SHA3-512 hash, for internal callers with known lengths
 #[cfg(not(feature = "90s"))]
-pub fn hash_g(out: &mut [u8], input: &[u8], inlen: usize) {
+pub(crate) fn hash_g_unchecked(
+    out: &mut [u8],
+    input: &[u8],
+    inlen: usize,
+) {
     sha3_512(out, input, inlen);
 }
 
-/// Computes SHA2-512 hash in 90s mode
+// Computes SHA2-512 hash in 90s mode
 #[cfg(feature = "90s")]
-pub fn hash_g(out: &mut [u8], input: &[u8], inlen: usize) {
+pub(crate) fn hash_g_unchecked(
+    out: &mut [u8],
+    input: &[u8],
+    inlen: usize,
+) {
     let mut hasher = Sha512::new();
     hasher.update(&input[..inlen]);
     let digest = hasher.finalize();
@@ -182,7 +295,10 @@ pub fn kyber_shake128_absorb(
     extseed[..KYBER_SYM_BYTES].copy_from_slice(input);
     extseed[KYBER_SYM_BYTES] = x;
     extseed[KYBER_SYM_BYTES + 1] = y;
+    #[cfg(not(feature = "turboshake"))]
     shake128_absorb_once(s, &extseed, KYBER_SYM_BYTES + 2);
+    #[cfg(feature = "turboshake")]
+    turboshake128_absorb_once(s, &extseed, KYBER_SYM_BYTES + 2);
 }
 
 /// Squeeze step of SHAKE128 XOF in non-90s mode
@@ -192,7 +308,10 @@ pub fn kyber_shake128_squeezeblocks(
     nblocks: usize,
     s: &mut KeccakState,
 ) {
+    #[cfg(not(feature = "turboshake"))]
     shake128_squeezeblocks(output, nblocks, s);
+    #[cfg(feature = "turboshake")]
+    turboshake128_squeezeblocks(output, nblocks, s);
 }
 
 /// Usage of SHAKE256 as a PRF in non-90s mode
@@ -206,5 +325,8 @@ pub fn shake256_prf(
     let mut extkey = [0u8; KYBER_SYM_BYTES + 1];
     extkey[..KYBER_SYM_BYTES].copy_from_slice(key);
     extkey[KYBER_SYM_BYTES] = nonce;
+    #[cfg(not(feature = "turboshake"))]
     shake256(output, outlen, &extkey, KYBER_SYM_BYTES + 1);
+    #[cfg(feature = "turboshake")]
+    turboshake256(output, outlen, &extkey, KYBER_SYM_BYTES + 1);
 }
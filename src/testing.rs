@@ -0,0 +1,149 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! RNGs for testing how downstream code handles KyberLib failures.
+//!
+//! `FailingRng` and `CountingRng` do not produce random output: they
+//! exist to drive the `KyberLibError::RandomBytesGenerationSized` paths
+//! of `keypair`, `encapsulate` and the key exchanges from unit tests.
+//! `labeled_rng` gives a reproducible stream for a test name. Never use
+//! any of them to generate real keys.
+
+use crate::{fips202::shake256, params::KYBER_SYM_BYTES};
+use core::num::NonZeroU32;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, Error, RngCore, SeedableRng};
+
+// The rand_core error both generators report
+fn rng_error() -> Error {
+    match NonZeroU32::new(Error::CUSTOM_START) {
+        Some(code) => Error::from(code),
+        None => unreachable!(),
+    }
+}
+
+/// An RNG whose every fill fails.
+///
+/// ### Example
+/// ```
+/// use kyberlib::{testing::FailingRng, *};
+///
+/// assert_eq!(
+///     keypair(&mut FailingRng),
+///     Err(KyberLibError::RandomBytesGenerationSized { requested: 32 })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FailingRng;
+
+impl RngCore for FailingRng {
+    fn next_u32(&mut self) -> u32 {
+        panic!("FailingRng always fails")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        panic!("FailingRng always fails")
+    }
+
+    fn fill_bytes(&mut self, _dest: &mut [u8]) {
+        panic!("FailingRng always fails")
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        _dest: &mut [u8],
+    ) -> Result<(), Error> {
+        Err(rng_error())
+    }
+}
+
+impl CryptoRng for FailingRng {}
+
+/// An RNG that serves `limit` bytes, then fails.
+///
+/// A fill that would go past the limit fails as a whole and serves
+/// nothing, so a limit between two draws makes the second one fail.
+/// The bytes are a simple counter, not random.
+///
+/// ### Example
+/// ```
+/// use kyberlib::{testing::CountingRng, *};
+///
+/// // Key generation draws 32 bytes twice
+/// assert!(keypair(&mut CountingRng::new(64)).is_ok());
+/// assert_eq!(
+///     keypair(&mut CountingRng::new(63)),
+///     Err(KyberLibError::RandomBytesGenerationSized { requested: 32 })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CountingRng {
+    limit: usize,
+    used: usize,
+    counter: u8,
+}
+
+impl CountingRng {
+    /// Creates an RNG that fails once `limit` bytes have been served.
+    pub fn new(limit: usize) -> Self {
+        CountingRng {
+            limit,
+            used: 0,
+            counter: 0,
+        }
+    }
+
+    /// Returns the number of bytes served so far.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+}
+
+impl RngCore for CountingRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.try_fill_bytes(dest).is_err() {
+            panic!("CountingRng limit of {} bytes reached", self.limit);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        if dest.len() > self.limit - self.used {
+            return Err(rng_error());
+        }
+        for byte in dest.iter_mut() {
+            *byte = self.counter;
+            self.counter = self.counter.wrapping_add(1);
+        }
+        self.used += dest.len();
+        Ok(())
+    }
+}
+
+impl CryptoRng for CountingRng {}
+
+/// Returns a ChaCha20 RNG seeded with `SHAKE256(label)`.
+///
+/// The same label always yields the same stream, so a test can name
+/// its randomness instead of spelling out a seed array.
+///
+/// ### Example
+/// ```
+/// use kyberlib::{testing::labeled_rng, *};
+///
+/// let a = keypair(&mut labeled_rng("alice"))?;
+/// assert_eq!(a, keypair(&mut labeled_rng("alice"))?);
+/// # Ok::<(), KyberLibError>(())
+/// ```
+pub fn labeled_rng(label: &str) -> impl RngCore + CryptoRng {
+    let mut seed = [0u8; KYBER_SYM_BYTES];
+    shake256(&mut seed, KYBER_SYM_BYTES, label.as_bytes(), label.len());
+    ChaCha20Rng::from_seed(seed)
+}
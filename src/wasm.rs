@@ -23,7 +23,8 @@ pub fn keypair() -> Result<Keys, JsError> {
             pubkey: Box::new(keys.public),
             secret: Box::new(keys.secret),
         }),
-        Err(KyberLibError::RandomBytesGeneration) => {
+        Err(KyberLibError::RandomBytesGeneration)
+        | Err(KyberLibError::RandomBytesGenerationSized { .. }) => {
             Err(JsError::new("Error trying to fill random bytes"))
         }
         _ => Err(JsError::new("The keypair could not be generated")),
@@ -82,7 +83,41 @@ pub fn decapsulate(
     }
 }
 
+/// Run a full KEM exchange in a single call.
+///
+/// Generates a key pair, encapsulates to its public key and checks
+/// that decapsulation recovers the same shared secret, returning every
+/// value at once so demos cross the JS/wasm boundary only once.
+///
+/// # Errors
+///
+/// Returns a `JsError` if key generation or encapsulation fails, or if
+/// the decapsulated shared secret does not match.
+#[wasm_bindgen]
+pub fn kem_demo() -> Result<KemDemo, JsError> {
+    let mut rng = OsRng {};
+    let keys = api::keypair(&mut rng).map_err(|_| {
+        JsError::new("The keypair could not be generated")
+    })?;
+    let (ct, ss) = api::encapsulate(&keys.public, &mut rng)
+        .map_err(|_| JsError::new("Encapsulation failed"))?;
+    match api::decapsulate(&ct, &keys.secret) {
+        Ok(decapsulated) if decapsulated == ss => Ok(KemDemo {
+            pubkey: Box::new(keys.public),
+            secret: Box::new(keys.secret),
+            ciphertext: Box::new(ct),
+            sharedSecret: Box::new(ss),
+        }),
+        _ => Err(JsError::new("Decapsulation failed")),
+    }
+}
+
 /// Represents Kyber key pair.
+///
+/// Dropping a `Keys`, or letting the JavaScript garbage collector free
+/// it, releases the secret key without wiping it, so it may linger in
+/// linear memory. Call `free_secret` to zero it once it is no longer
+/// needed.
 #[wasm_bindgen]
 #[derive(Debug)]
 pub struct Keys {
@@ -127,6 +162,28 @@ impl Keys {
     pub fn secret(&self) -> Box<[u8]> {
         self.secret.clone()
     }
+
+    /// Zero the secret key in place.
+    ///
+    /// Afterwards the `secret` getter returns all zeros. Copies already
+    /// handed to JavaScript by the getter are not affected.
+    pub fn free_secret(&mut self) {
+        wipe(&mut self.secret);
+    }
+}
+
+// Rust-only helpers, not exported to JavaScript
+impl Keys {
+    /// Copies the keys into a core [`Keypair`], e.g. to drive the `Uake`
+    /// and `Ake` APIs from Rust code that received a `Keys`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KyberLibError::InvalidInput` if either key has an
+    /// incorrect length.
+    pub fn to_keypair(&self) -> Result<Keypair, KyberLibError> {
+        Keypair::import_unchecked(&self.pubkey, &self.secret)
+    }
 }
 
 #[wasm_bindgen]
@@ -166,9 +223,21 @@ impl Kex {
     /// # Arguments
     ///
     /// * `ciphertext` - The ciphertext as a boxed slice of bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsError`, leaving the stored ciphertext unchanged, if
+    /// the ciphertext size is incorrect.
     #[wasm_bindgen(setter)]
-    pub fn set_ciphertext(&mut self, ciphertext: Box<[u8]>) {
+    pub fn set_ciphertext(
+        &mut self,
+        ciphertext: Box<[u8]>,
+    ) -> Result<(), JsError> {
+        if ciphertext.len() != KYBER_CIPHERTEXT_BYTES {
+            return Err(JsError::new("Invalid ciphertext length"));
+        }
         self.ciphertext = ciphertext;
+        Ok(())
     }
 
     /// Set the shared secret.
@@ -176,9 +245,59 @@ impl Kex {
     /// # Arguments
     ///
     /// * `sharedSecret` - The shared secret as a boxed slice of bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `JsError`, leaving the stored shared secret unchanged,
+    /// if the shared secret size is incorrect.
     #[wasm_bindgen(setter)]
-    pub fn set_sharedSecret(&mut self, sharedSecret: Box<[u8]>) {
+    pub fn set_sharedSecret(
+        &mut self,
+        sharedSecret: Box<[u8]>,
+    ) -> Result<(), JsError> {
+        if sharedSecret.len() != KYBER_SHARED_SECRET_BYTES {
+            return Err(JsError::new("Invalid shared secret length"));
+        }
         self.sharedSecret = sharedSecret;
+        Ok(())
+    }
+}
+
+/// Represents the result of [`kem_demo`]: a key pair together with a
+/// ciphertext and the shared secret it encapsulates.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct KemDemo {
+    pubkey: Box<[u8]>,
+    secret: Box<[u8]>,
+    ciphertext: Box<[u8]>,
+    sharedSecret: Box<[u8]>,
+}
+
+#[wasm_bindgen]
+impl KemDemo {
+    /// Get the public key.
+    #[wasm_bindgen(getter)]
+    pub fn pubkey(&self) -> Box<[u8]> {
+        self.pubkey.clone()
+    }
+
+    /// Get the secret key.
+    #[wasm_bindgen(getter)]
+    pub fn secret(&self) -> Box<[u8]> {
+        self.secret.clone()
+    }
+
+    /// Get the ciphertext.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext(&self) -> Box<[u8]> {
+        self.ciphertext.clone()
+    }
+
+    /// Get the shared secret.
+    #[wasm_bindgen(getter)]
+    pub fn sharedSecret(&self) -> Box<[u8]> {
+        self.sharedSecret.clone()
     }
 }
 
@@ -0,0 +1,92 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Regression guard for the AVX2 backend.
+//!
+//! Replays seeded key generation, encapsulation and decapsulation and
+//! compares SHA3-256 digests of every output with values computed by
+//! the reference backend. Run with
+//! `cargo test --features avx2 --test avx2_vs_reference` on x86_64.
+//! The digests are for the default Kyber768 SHAKE build.
+
+#![cfg(all(
+    feature = "avx2",
+    target_arch = "x86_64",
+    not(feature = "kyber512"),
+    not(feature = "kyber1024"),
+    not(feature = "90s")
+))]
+
+use kyberlib::{kem::encrypt_message, symmetric::hash_h, *};
+
+// (seed byte, pk, sk, ct, ss, implicit-rejection ss) digests from the
+// reference backend. The keypair seed is 64 copies of the seed byte and
+// the encapsulation coins 32 copies of its bitwise complement.
+const VECTORS: [(u8, &str, &str, &str, &str, &str); 3] = [
+    (
+        0x00,
+        "41b5e9a0e800819ea5b04df9adf3ecef916f8f44f6abf193fbc959572615908d",
+        "d9174db916da8170abfe6a76fc51525e70288e8736e3c9d8992015d316e8c58e",
+        "db88b74c638e8925f90df4915aa0f9297aefc2ae3dc5e690f23664853adf2059",
+        "fd95c873d93204e656e8602c9e2245f92e4eb7fa006b6bccbcabfb200e944ce6",
+        "1c4570faca669a6fa50e72c153f093d25090ae7ba59b9761f59b65b6bb122c01",
+    ),
+    (
+        0x5a,
+        "37f303e69541087af40ce865363f569357ab28ad618ca23091018d510f9dca22",
+        "f83d6557bf0f8e5791715a8fbb4ffe5df7ec748e25ea27f7661e96d2b338d69e",
+        "8d4a49c47430aa8795b6a1290614be4d5c1f2074c73b49fab4ddca79028ee558",
+        "38281d51ea6add9278b1407a71144ba19302ec9586022e2b68b0c4ee3dbc5329",
+        "e08d40fc5ad8223c6e43e2d48d8f4a1c70d294aa8d652229d14858b7db7ad3ed",
+    ),
+    (
+        0xc3,
+        "4982f87c92e28e508b4aec3973a31378c6985e1c408c692182eb5ac0ed056c0c",
+        "0c8ef23e5a3f5e97ecda07752c7d981f18c1b8d4fd258d2f5cedd04c2d46d004",
+        "4621007e1a4b93a45b97d84b641447a2134b7f26e3a52cc986f6fb8c76381ecf",
+        "516b0be34cbebe629954b5a458195b65016d9150de811fa3ca3c113ef186cf42",
+        "8f211a19373fa4ca9cfd78674ba026a2b0c2ec8aad926a21fda0c16dce18f4c3",
+    ),
+];
+
+fn digest(bytes: &[u8]) -> String {
+    let mut hash = [0u8; 32];
+    hash_h(&mut hash, bytes, bytes.len()).unwrap();
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn avx2_matches_reference() {
+    for (seed, pk, sk, ct, ss, rejected) in VECTORS {
+        let keys = derive(&[seed; 64]).unwrap();
+        assert_eq!(digest(&keys.public), pk, "pk, seed {:#04x}", seed);
+        assert_eq!(digest(&keys.secret), sk, "sk, seed {:#04x}", seed);
+
+        let mut ciphertext = [0u8; KYBER_CIPHERTEXT_BYTES];
+        let mut shared = [0u8; KYBER_SHARED_SECRET_BYTES];
+        encrypt_message(
+            &mut ciphertext,
+            &mut shared,
+            &keys.public,
+            &mut rand::thread_rng(),
+            Some(&[!seed; 32]),
+        )
+        .unwrap();
+        assert_eq!(digest(&ciphertext), ct, "ct, seed {:#04x}", seed);
+        assert_eq!(digest(&shared), ss, "ss, seed {:#04x}", seed);
+        assert_eq!(
+            decapsulate(&ciphertext, &keys.secret).unwrap(),
+            shared
+        );
+
+        // A flipped bit takes the implicit-rejection path
+        ciphertext[0] ^= 1;
+        let fallback = decapsulate(&ciphertext, &keys.secret).unwrap();
+        assert_eq!(
+            digest(&fallback),
+            rejected,
+            "rejection, seed {:#04x}",
+            seed
+        );
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Statistical timing test of `decapsulate`, in the style of dudect.
+//!
+//! The test is ignored by default because unoptimized builds and busy
+//! machines make the timings too noisy. Run it with `cargo test
+//! --release --features ct-tests --test ct_decapsulate -- --ignored`.
+//!
+//! Valid ciphertexts and the same ciphertexts with one bit flipped,
+//! which take the implicit-rejection path, are decapsulated in random
+//! order. The slowest measurements are cropped to drop
+//! interrupts and cache misses, then Welch's t-test compares the two
+//! timing distributions. As in dudect, a |t| above 10 is reported as a
+//! timing difference between the classes.
+
+#![cfg(feature = "ct-tests")]
+
+use kyberlib::*;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use std::{hint::black_box, time::Instant};
+
+// Measurements per run, split randomly between the two classes
+const SAMPLES: usize = 20_000;
+// Ciphertexts per class, cycled through so one input cannot dominate
+const POOL: usize = 64;
+// Share of the fastest measurements kept
+const KEEP: f64 = 0.9;
+// |t| above which the classes are considered distinguishable
+const THRESHOLD: f64 = 10.0;
+
+// Mean and variance of a sample
+fn moments(xs: &[f64]) -> (f64, f64) {
+    let n = xs.len() as f64;
+    let mean = xs.iter().sum::<f64>() / n;
+    let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>()
+        / (n - 1.0);
+    (mean, var)
+}
+
+// Welch's t statistic of two independent samples
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let (ma, va) = moments(a);
+    let (mb, vb) = moments(b);
+    (ma - mb) / (va / a.len() as f64 + vb / b.len() as f64).sqrt()
+}
+
+#[test]
+#[ignore = "timing-sensitive, run in release with --ignored"]
+fn decapsulate_time_independent_of_validity() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let keys = keypair(&mut rng).unwrap();
+    let mut valid = Vec::with_capacity(POOL);
+    let mut invalid = Vec::with_capacity(POOL);
+    for _ in 0..POOL {
+        let (ct, _) = encapsulate(&keys.public, &mut rng).unwrap();
+        let mut bad = ct;
+        bad[rng.gen_range(0..KYBER_CIPHERTEXT_BYTES)] ^= 1;
+        valid.push(ct);
+        invalid.push(bad);
+    }
+
+    // Warm up caches and frequency scaling before measuring
+    for ct in valid.iter().chain(invalid.iter()) {
+        let _ = black_box(decapsulate(black_box(ct), &keys.secret));
+    }
+
+    let mut timings = Vec::with_capacity(SAMPLES);
+    for i in 0..SAMPLES {
+        let is_valid = rng.next_u32() & 1 == 1;
+        let ct = if is_valid { &valid } else { &invalid }[i % POOL];
+        let start = Instant::now();
+        let _ = black_box(decapsulate(black_box(&ct), &keys.secret));
+        let elapsed = start.elapsed().as_nanos() as f64;
+        timings.push((is_valid, elapsed));
+    }
+
+    let mut sorted: Vec<f64> =
+        timings.iter().map(|&(_, t)| t).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let cutoff = sorted[(SAMPLES as f64 * KEEP) as usize];
+    let class = |want: bool| -> Vec<f64> {
+        timings
+            .iter()
+            .filter(|&&(is_valid, t)| is_valid == want && t <= cutoff)
+            .map(|&(_, t)| t)
+            .collect()
+    };
+    let t = welch_t(&class(true), &class(false));
+    assert!(
+        t.abs() < THRESHOLD,
+        "decapsulate timing depends on ciphertext validity (t = {:.2})",
+        t
+    );
+}
@@ -0,0 +1,87 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Byte-for-byte comparison against the `pqc_kyber` crate.
+//!
+//! Run with `cargo test --features interop-tests --test interop_pqc`.
+//! Checked against `pqc_kyber` 0.7.1; every 0.7.x release implements
+//! the same round 3 Kyber and is expected to match. Only the default
+//! Kyber768 SHAKE build is compared, since the dev-dependency is built
+//! with its own default level.
+
+#![cfg(all(
+    feature = "interop-tests",
+    not(feature = "kyber512"),
+    not(feature = "kyber1024"),
+    not(feature = "90s"),
+    not(feature = "turboshake")
+))]
+
+use kyberlib::{
+    kem::{decrypt_message, encrypt_message, generate_key_pair},
+    *,
+};
+
+// Replays fixed bytes, so pqc_kyber draws the same seeds that are
+// passed to kyberlib explicitly
+struct ReplayRng<'a>(&'a [u8]);
+
+impl RngCore for ReplayRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let (head, rest) = self.0.split_at(dest.len());
+        dest.copy_from_slice(head);
+        self.0 = rest;
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ReplayRng<'_> {}
+
+#[test]
+fn test_matches_pqc_kyber() {
+    for i in 0..16u8 {
+        let d = [i; KYBER_SYM_BYTES];
+        let z = [i.wrapping_add(0x40); KYBER_SYM_BYTES];
+        let m = [i.wrapping_add(0x80); KYBER_SYM_BYTES];
+
+        // kyberlib with explicit seeds
+        let mut rng = rand::thread_rng();
+        let mut pk = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        let mut sk = [0u8; KYBER_SECRET_KEY_BYTES];
+        generate_key_pair(&mut pk, &mut sk, &mut rng, Some((&d, &z)))
+            .unwrap();
+        let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+        let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+        encrypt_message(&mut ct, &mut ss, &pk, &mut rng, Some(&m))
+            .unwrap();
+        let mut ss_dec = [0u8; KYBER_SHARED_SECRET_BYTES];
+        decrypt_message(&mut ss_dec, &ct, &sk);
+        assert_eq!(ss_dec, ss);
+
+        // pqc_kyber drawing the same seeds from its RNG
+        let seeds = [d, z].concat();
+        let keys = pqc_kyber::keypair(&mut ReplayRng(&seeds)).unwrap();
+        assert_eq!(keys.public, pk);
+        assert_eq!(keys.secret, sk);
+        let (other_ct, other_ss) =
+            pqc_kyber::encapsulate(&pk, &mut ReplayRng(&m)).unwrap();
+        assert_eq!(other_ct, ct);
+        assert_eq!(other_ss, ss);
+        assert_eq!(pqc_kyber::decapsulate(&ct, &sk).unwrap(), ss);
+    }
+}
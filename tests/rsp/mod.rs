@@ -0,0 +1,93 @@
+use kyberlib::{KYBER_90S, KYBER_SECURITY_PARAMETER};
+use std::fs;
+use std::path::PathBuf;
+
+// A single vector from a NIST `PQCkemKAT` response file
+#[derive(Debug, Default)]
+pub(crate) struct RspKat {
+    pub(crate) count: usize,
+    pub(crate) seed: Vec<u8>,
+    pub(crate) pk: Vec<u8>,
+    pub(crate) sk: Vec<u8>,
+    pub(crate) ct: Vec<u8>,
+    pub(crate) ss: Vec<u8>,
+}
+
+// Get .rsp filename based on security level and if 90s mode
+fn get_filename() -> String {
+    let mut filename = match KYBER_SECURITY_PARAMETER {
+        2 => "kyber512".to_string(),
+        3 => "kyber768".to_string(),
+        4 => "kyber1024".to_string(),
+        _ => panic!("No security level set"),
+    };
+    if KYBER_90S {
+        filename.push_str("-90s");
+    }
+    filename.push_str(".rsp");
+    filename
+}
+
+// .rsp path
+fn get_rsp_filepath() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend(&["tests", "KAT", &get_filename()]);
+    path
+}
+
+// Parses the `key = value` blocks of a NIST .rsp file
+pub(crate) fn parse_rsp(contents: &str) -> Vec<RspKat> {
+    let mut kats = Vec::new();
+    let mut current: Option<RspKat> = None;
+    for line in contents.lines().map(str::trim) {
+        // Skip the algorithm header and blank separators
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => panic!("Malformed .rsp line: {}", line),
+        };
+        if key == "count" {
+            if let Some(kat) = current.take() {
+                kats.push(kat);
+            }
+            current = Some(RspKat {
+                count: value.parse().expect("Invalid count"),
+                ..Default::default()
+            });
+            continue;
+        }
+        let kat = current.as_mut().expect("Field before count");
+        match key {
+            "seed" => kat.seed = decode_hex(value),
+            "pk" => kat.pk = decode_hex(value),
+            "sk" => kat.sk = decode_hex(value),
+            "ct" => kat.ct = decode_hex(value),
+            "ss" => kat.ss = decode_hex(value),
+            _ => panic!("Unknown .rsp field: {}", key),
+        }
+    }
+    kats.extend(current);
+    kats
+}
+
+// Loads the .rsp vectors for the compiled security level
+pub(crate) fn load_rsp() -> Vec<RspKat> {
+    let path = get_rsp_filepath();
+    println!("Using KAT file: {}", path.display());
+    let contents =
+        fs::read_to_string(path).expect("Error loading .rsp file");
+    parse_rsp(&contents)
+}
+
+// Decodes hex string into a vector of bytes
+pub(crate) fn decode_hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16)
+                .expect("Hex string decoding")
+        })
+        .collect::<Vec<u8>>()
+}
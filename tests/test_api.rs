@@ -122,6 +122,48 @@ mod tests {
         assert_eq!(imported_keypair.secret, keypair.secret);
     }
 
+    // Test for Keypair::import_unchecked method
+    #[test]
+    fn test_keypair_import_unchecked() {
+        // Initialize a random number generator
+        let mut rng = OsRng;
+        // Generate keypair
+        let keypair = keypair(&mut rng).unwrap();
+        // Import keypair without the round-trip check
+        let imported_keypair =
+            Keypair::import_unchecked(&keypair.public, &keypair.secret)
+                .unwrap();
+        // Assert equality of the imported keypair and the original keypair
+        assert_eq!(imported_keypair, keypair);
+        // Encapsulate to the imported public key and decapsulate with
+        // the imported secret key
+        let (ciphertext, shared_secret1) =
+            encapsulate(&imported_keypair.public, &mut rng).unwrap();
+        let shared_secret2 =
+            decapsulate(&ciphertext, &imported_keypair.secret).unwrap();
+        // Assert equality of the shared secrets
+        assert_eq!(shared_secret1, shared_secret2);
+    }
+
+    // Test for handling of invalid inputs in Keypair::import_unchecked
+    #[test]
+    fn test_keypair_import_unchecked_invalid_input() {
+        // Define public key and secret key with incorrect lengths
+        let invalid_public_key = [0u8; KYBER_PUBLIC_KEY_BYTES - 1];
+        let invalid_secret_key = [0u8; KYBER_SECRET_KEY_BYTES - 1];
+        let public_key = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        let secret_key = [0u8; KYBER_SECRET_KEY_BYTES];
+        // Assert error handling for Keypair::import_unchecked
+        assert_eq!(
+            Keypair::import_unchecked(&invalid_public_key, &secret_key),
+            Err(KyberLibError::InvalidInput)
+        );
+        assert_eq!(
+            Keypair::import_unchecked(&public_key, &invalid_secret_key),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
     // Test for keypairfrom function
     #[test]
     fn test_keypairfrom() {
@@ -141,6 +183,41 @@ mod tests {
         assert_eq!(new_keypair.secret, keypair.secret);
     }
 
+    // keypairfrom accepts a matching pair and rejects a mismatched one
+    #[test]
+    fn test_keypairfrom_match_and_mismatch() {
+        let mut rng = OsRng;
+        let alice = keypair(&mut rng).unwrap();
+        let bob = keypair(&mut rng).unwrap();
+
+        let (mut public, mut secret) = (alice.public, alice.secret);
+        let imported =
+            keypairfrom(&mut public, &mut secret, &mut rng).unwrap();
+        assert_eq!(imported.public, alice.public);
+
+        let (mut public, mut secret) = (alice.public, bob.secret);
+        assert_eq!(
+            keypairfrom(&mut public, &mut secret, &mut rng),
+            Err(KyberLibError::InvalidKey)
+        );
+    }
+
+    // A mismatched pair leaves the caller's buffers untouched
+    #[test]
+    fn test_keypairfrom_mismatch_keeps_inputs() {
+        let mut rng = OsRng;
+        let alice = keypair(&mut rng).unwrap();
+        let bob = keypair(&mut rng).unwrap();
+
+        let (mut public, mut secret) = (alice.public, bob.secret);
+        assert_eq!(
+            keypairfrom(&mut public, &mut secret, &mut rng),
+            Err(KyberLibError::InvalidKey)
+        );
+        assert_eq!(public, alice.public);
+        assert_eq!(secret, bob.secret);
+    }
+
     // Test for handling of invalid inputs in keypairfrom
     #[test]
     fn test_keypairfrom_invalid_input() {
@@ -183,6 +260,7 @@ mod tests {
 
     // Test for handling of invalid inputs in Keypair::generate
     #[test]
+    #[allow(unused_assignments)]
     fn test_keypair_generate_invalid_input() {
         // Initialize a random number generator
         let mut rng = OsRng;
@@ -236,7 +314,7 @@ mod tests {
 
     // Test for handling of invalid inputs in public
     #[test]
-    fn test_public_invalid_secret_key_length() {
+    fn test_publicinvalid_secret_key_length() {
         let invalid_secret_key = [0u8; KYBER_SECRET_KEY_BYTES - 1];
         assert_eq!(
             public(&invalid_secret_key).len(),
@@ -263,6 +341,87 @@ mod tests {
         assert_eq!(keypair.secret.len(), KYBER_SECRET_KEY_BYTES);
     }
 
+    // derive_and_wipe matches derive and leaves the seed zeroed
+    #[test]
+    fn test_derive_and_wipe() {
+        let original: [u8; 64] = core::array::from_fn(|i| i as u8 + 1);
+        let mut seed = original;
+        let keypair = derive_and_wipe(&mut seed).unwrap();
+        assert_eq!(seed, [0u8; 64]);
+        assert_eq!(keypair, derive(&original).unwrap());
+    }
+
+    // Keygen from a fixed seed matches the little-endian C reference,
+    // so keys are byte-identical whatever the host's endianness
+    #[cfg(not(any(feature = "90s", feature = "turboshake")))]
+    #[test]
+    fn test_derive_matches_reference_vector() {
+        #[cfg(feature = "kyber512")]
+        const EXPECTED: (&str, &str) = (
+            "b0448d37ecbcf2918a73bcc1b3d8174e322df9863ce92f5ea0f6bd6bca7da05e",
+            "fce230daa8dd4f465ddcc2b8dc864d0c604983f97447e91524dde80ac913f522",
+        );
+        #[cfg(not(any(feature = "kyber512", feature = "kyber1024")))]
+        const EXPECTED: (&str, &str) = (
+            "0df97a95f3e9fcaa29e04ffc0b246f17b50a19e74c90f88dadc41837dddcb6d1",
+            "157699f1afdbe4acff52ab00e6ef075bb6c267280d4c1e24fd907b43c9bf0ce5",
+        );
+        #[cfg(feature = "kyber1024")]
+        const EXPECTED: (&str, &str) = (
+            "8db2bf117dfa02aaa1994384bbf77a1054cc601b1d3c6fb6b215823f55e6e7bf",
+            "ff10fb5eaff0ddf9bb5eaf2db484da4b33f918f025883ba98295372f2f54a18d",
+        );
+        let seed: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let keypair = derive(&seed).unwrap();
+        // SHA3-256 digests of the public and secret keys
+        let to_hex = |bytes: &[u8]| -> String {
+            let mut digest = [0u8; 32];
+            symmetric::hash_h(&mut digest, bytes, bytes.len()).unwrap();
+            digest.iter().map(|b| format!("{:02x}", b)).collect()
+        };
+        assert_eq!(to_hex(&keypair.public), EXPECTED.0);
+        assert_eq!(to_hex(&keypair.secret), EXPECTED.1);
+    }
+
+    // derive_from_32 derives from SHAKE256(seed, 64) as d || z
+    #[test]
+    fn test_derive_from_32() {
+        let seed: [u8; 32] = core::array::from_fn(|i| i as u8);
+        // SHAKE256 of 00 01 .. 1f, 64 bytes of output
+        let expanded = "69f07c8840ce80024db30939882c3d5bbc9c98b3e31e4513ebd2ca9b4503cdd3\
+                        c9c90742452c7173d4a75ac49163e14ee0cc24ef7035b272d19a7af1099b333f";
+        let expanded: Vec<u8> = (0..expanded.len())
+            .step_by(2)
+            .map(|i| {
+                u8::from_str_radix(&expanded[i..i + 2], 16).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            derive_from_32(&seed).unwrap(),
+            derive(&expanded).unwrap()
+        );
+    }
+
+    // rotate is deterministic per epoch and differs across epochs
+    #[test]
+    fn test_rotate() {
+        let seed: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let epoch0 = rotate(&seed, 0).unwrap();
+        assert_eq!(epoch0, rotate(&seed, 0).unwrap());
+        let epoch1 = rotate(&seed, 1).unwrap();
+        assert_ne!(epoch0.public, epoch1.public);
+        assert_ne!(epoch0.secret, epoch1.secret);
+        assert_ne!(epoch0, derive(&seed).unwrap());
+    }
+
+    // generate_public_only returns the same public key as derive
+    #[test]
+    fn test_generate_public_only() {
+        let seed: [u8; 64] = core::array::from_fn(|i| 255 - i as u8);
+        let public = generate_public_only(&seed).unwrap();
+        assert_eq!(public, derive(&seed).unwrap().public);
+    }
+
     // Test for valid input for encapsulation and decapsulation
     #[test]
     fn test_encapsulate_decapsulate_valid_input() {
@@ -276,4 +435,927 @@ mod tests {
             decapsulate(&ciphertext, &keypair.secret).unwrap();
         assert_eq!(shared_secret, decapsulated_secret);
     }
+
+    // SecretKeyBuf derefs to the full secret key slice
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_secret_key_buf_deref() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        // Starts zeroed and derefs to KYBER_SECRET_KEY_BYTES bytes
+        let mut buf = SecretKeyBuf::new();
+        assert_eq!(buf.len(), KYBER_SECRET_KEY_BYTES);
+        assert!(buf.iter().all(|&b| b == 0));
+        // Write through DerefMut and read back through Deref
+        buf.copy_from_slice(&keys.secret);
+        assert_eq!(&buf[..], &keys.secret[..]);
+        // Converts into the matching keypair
+        let imported = buf.into_keypair(&keys.public).unwrap();
+        assert_eq!(imported, keys);
+    }
+
+    // SecretKeyBuf is wiped when dropped
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_secret_key_buf_zeroized_on_drop() {
+        use core::mem::ManuallyDrop;
+        // Fill with a sentinel and keep a clone for comparison
+        let mut buf = ManuallyDrop::new(SecretKeyBuf::new());
+        buf.fill(0xA5);
+        let sentinel = (*buf).clone();
+        // Run the destructor in place; the storage stays valid
+        unsafe { ManuallyDrop::drop(&mut buf) };
+        let snapshot: Vec<u8> = buf.to_vec();
+        assert_ne!(&snapshot[..], &sentinel[..]);
+        assert!(snapshot.iter().all(|&b| b == 0));
+    }
+
+    // Self-test reproduces the expected shared secret for this level
+    #[test]
+    fn test_self_test() {
+        assert_eq!(self_test(), Ok(()));
+    }
+
+    // Both parties stretch the shared secret to the same 64 bytes
+    #[test]
+    fn test_derive_shared_secret_64_bytes() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let (ct, alice_ss) =
+            encapsulate(&keys.public, &mut rng).unwrap();
+        let bob_ss = decapsulate(&ct, &keys.secret).unwrap();
+        // Stretch on each side
+        let mut alice_okm = [0u8; 64];
+        let mut bob_okm = [0u8; 64];
+        derive_shared_secret(&alice_ss, &mut alice_okm);
+        derive_shared_secret(&bob_ss, &mut bob_okm);
+        assert_eq!(alice_okm, bob_okm);
+        // The output is not a copy of the shared secret
+        assert_ne!(
+            &alice_okm[..KYBER_SHARED_SECRET_BYTES],
+            &alice_ss[..]
+        );
+        assert_ne!(alice_okm[KYBER_SHARED_SECRET_BYTES..], [0u8; 32]);
+    }
+
+    // encapsulate_into matches encapsulate under the same seeded RNG
+    #[test]
+    fn test_encapsulate_into_matches_encapsulate() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let keys = keypair(&mut OsRng).unwrap();
+        // Encapsulate into owned arrays
+        let mut rng = StdRng::seed_from_u64(42);
+        let (ct, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+        // Encapsulate into caller buffers with an identically seeded RNG
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut ct_out = [0u8; KYBER_CIPHERTEXT_BYTES];
+        let mut ss_out = [0u8; KYBER_SHARED_SECRET_BYTES];
+        encapsulate_into(
+            &keys.public,
+            &mut ct_out,
+            &mut ss_out,
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(ct_out, ct);
+        assert_eq!(ss_out, ss);
+    }
+
+    // encapsulate_prepared matches encapsulate under the same seeded RNG
+    #[test]
+    fn test_encapsulate_prepared_matches_encapsulate() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let keys = keypair(&mut OsRng).unwrap();
+        let prepared = PreparedPublicKey::new(&keys.public).unwrap();
+        assert_eq!(prepared.public(), &keys.public);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut prepared_rng = StdRng::seed_from_u64(7);
+        for _ in 0..4 {
+            let (ct, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+            let (prepared_ct, prepared_ss) =
+                encapsulate_prepared(&prepared, &mut prepared_rng)
+                    .unwrap();
+            assert_eq!(prepared_ct, ct);
+            assert_eq!(prepared_ss, ss);
+            assert_eq!(decapsulate(&ct, &keys.secret).unwrap(), ss);
+        }
+        assert_eq!(
+            PreparedPublicKey::new(&keys.public[1..]).unwrap_err(),
+            KyberLibError::InvalidInput
+        );
+    }
+
+    // encapsulate_into rejects wrongly sized buffers
+    #[test]
+    fn test_encapsulate_into_invalid_input() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let mut ct_out = [0u8; KYBER_CIPHERTEXT_BYTES];
+        let mut ss_out = [0u8; KYBER_SHARED_SECRET_BYTES];
+        let mut short_ct = [0u8; KYBER_CIPHERTEXT_BYTES - 1];
+        let mut long_ss = [0u8; KYBER_SHARED_SECRET_BYTES + 1];
+        assert_eq!(
+            encapsulate_into(
+                &keys.public[1..],
+                &mut ct_out,
+                &mut ss_out,
+                &mut rng
+            ),
+            Err(KyberLibError::InvalidInput)
+        );
+        assert_eq!(
+            encapsulate_into(
+                &keys.public,
+                &mut short_ct,
+                &mut ss_out,
+                &mut rng
+            ),
+            Err(KyberLibError::InvalidInput)
+        );
+        assert_eq!(
+            encapsulate_into(
+                &keys.public,
+                &mut ct_out,
+                &mut long_ss,
+                &mut rng
+            ),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // Matching contexts agree on the shared secret
+    #[test]
+    fn test_encapsulate_with_context_matching() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let context = b"session-id-1234";
+        let (ct, ss) =
+            encapsulate_with_context(&keys.public, context, &mut rng)
+                .unwrap();
+        let decapsulated =
+            decapsulate_with_context(&ct, &keys.secret, context)
+                .unwrap();
+        assert_eq!(ss, decapsulated);
+    }
+
+    // Mismatched or missing contexts yield a different shared secret
+    #[test]
+    fn test_encapsulate_with_context_mismatched() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let (ct, ss) = encapsulate_with_context(
+            &keys.public,
+            b"session-a",
+            &mut rng,
+        )
+        .unwrap();
+        let wrong_context =
+            decapsulate_with_context(&ct, &keys.secret, b"session-b")
+                .unwrap();
+        assert_ne!(ss, wrong_context);
+        let no_context = decapsulate(&ct, &keys.secret).unwrap();
+        assert_ne!(ss, no_context);
+    }
+
+    // Keypair round-trips through an in-memory reader and writer
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_keypair_read_write_round_trip() {
+        use std::io::Cursor;
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        // Write public || secret
+        let mut cursor = Cursor::new(Vec::new());
+        keys.write_to(&mut cursor).unwrap();
+        assert_eq!(
+            cursor.get_ref().len(),
+            KYBER_PUBLIC_KEY_BYTES + KYBER_SECRET_KEY_BYTES
+        );
+        // Read it back
+        cursor.set_position(0);
+        let read = Keypair::read_from(&mut cursor).unwrap();
+        assert_eq!(read, keys);
+    }
+
+    // A truncated reader is an error rather than a partial keypair
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_keypair_read_from_truncated() {
+        use std::io::Cursor;
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let mut bytes = Vec::new();
+        keys.write_to(&mut bytes).unwrap();
+        // Drop the last byte of the secret key
+        let _ = bytes.pop();
+        let mut cursor = Cursor::new(bytes);
+        assert_eq!(
+            Keypair::read_from(&mut cursor),
+            Err(KyberLibError::InvalidLength)
+        );
+    }
+
+    // DecapStream yields one secret per ciphertext, then an error for
+    // a partial trailing chunk
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decap_stream() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let mut batch = Vec::new();
+        let mut expected = Vec::new();
+        for _ in 0..3 {
+            let (ct, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+            batch.extend_from_slice(&ct);
+            expected.push(ss);
+        }
+        batch.extend_from_slice(&[0u8; 10]);
+
+        let mut stream =
+            DecapStream::new(std::io::Cursor::new(batch), &keys.secret);
+        for ss in expected {
+            assert_eq!(stream.next(), Some(Ok(ss)));
+        }
+        assert_eq!(
+            stream.next(),
+            Some(Err(KyberLibError::InvalidInput))
+        );
+        assert_eq!(stream.next(), None);
+    }
+
+    // The all-zero key is trivial, a generated key is not
+    #[test]
+    fn test_is_trivial_public_key() {
+        let keys = keypair(&mut OsRng).unwrap();
+        assert!(!is_trivial_public_key(&keys.public));
+        assert!(is_trivial_public_key(&[0u8; KYBER_PUBLIC_KEY_BYTES]));
+        assert!(is_trivial_public_key(&keys.public[1..]));
+
+        // A coefficient of 0xfff is out of range
+        let mut bad = keys.public;
+        bad[0] = 0xff;
+        bad[1] |= 0x0f;
+        assert!(is_trivial_public_key(&bad));
+    }
+
+    // Only the all-zero shared secret is reported as zero
+    #[test]
+    fn test_shared_secret_is_zero() {
+        assert!(shared_secret_is_zero(
+            &[0u8; KYBER_SHARED_SECRET_BYTES]
+        ));
+        let mut last = [0u8; KYBER_SHARED_SECRET_BYTES];
+        last[KYBER_SHARED_SECRET_BYTES - 1] = 1;
+        assert!(!shared_secret_is_zero(&last));
+
+        let keys = keypair(&mut OsRng).unwrap();
+        let (ct, ss) = encapsulate(&keys.public, &mut OsRng).unwrap();
+        assert!(!shared_secret_is_zero(&ss));
+        let ss = decapsulate(&ct, &keys.secret).unwrap();
+        assert!(!shared_secret_is_zero(&ss));
+    }
+
+    // derive_keys is SHAKE256(ss || label), reproducible per label and
+    // independent across labels; the 150-byte label spans a rate block
+    #[test]
+    fn test_derive_keys() {
+        let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+        ss.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+        let long_label = [0xA5u8; 150];
+
+        let mut enc = [0u8; 200];
+        let mut mac = [0u8; 200];
+        let mut long = [0u8; 16];
+        derive_keys(
+            &ss,
+            &[b"enc", b"mac", &long_label],
+            &mut [&mut enc, &mut mac, &mut long],
+        );
+        assert_ne!(enc[..], mac[..]);
+
+        let mut again = [0u8; 200];
+        derive_keys(&ss, &[b"enc"], &mut [&mut again]);
+        assert_eq!(enc[..], again[..]);
+
+        let expected_head: [u8; 16] = [
+            0x52, 0x96, 0x64, 0x36, 0x19, 0x72, 0x3f, 0x27, 0xd5, 0x34,
+            0x0c, 0xfc, 0x62, 0x2c, 0xea, 0x0f,
+        ];
+        let expected_tail: [u8; 16] = [
+            0xde, 0xda, 0x22, 0x01, 0x1c, 0x91, 0xb7, 0x02, 0x55, 0x6d,
+            0xd2, 0xc0, 0x4a, 0x14, 0xfb, 0x36,
+        ];
+        let expected_long: [u8; 16] = [
+            0xe9, 0x61, 0x5c, 0x65, 0x21, 0x78, 0xde, 0xf6, 0x09, 0x34,
+            0x7b, 0xd9, 0xc0, 0x47, 0x5e, 0xb7,
+        ];
+        assert_eq!(enc[..16], expected_head);
+        assert_eq!(enc[184..], expected_tail);
+        assert_eq!(long, expected_long);
+    }
+
+    // decapsulate_with_pk agrees with decapsulate, including on
+    // ciphertexts that fail re-encryption
+    #[test]
+    fn test_decapsulate_with_pk_matches_decapsulate() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let pk = public(&keys.secret);
+        for _ in 0..8 {
+            let (mut ct, _) =
+                encapsulate(&keys.public, &mut rng).unwrap();
+            assert_eq!(
+                decapsulate_with_pk(&ct, &keys.secret, &pk),
+                decapsulate(&ct, &keys.secret)
+            );
+            ct[0] ^= 1;
+            assert_eq!(
+                decapsulate_with_pk(&ct, &keys.secret, &pk),
+                decapsulate(&ct, &keys.secret)
+            );
+        }
+        assert_eq!(
+            decapsulate_with_pk(&[0u8; 3], &keys.secret, &pk),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // Keypair::to_bytes / from_bytes round-trip the versioned blob
+    #[test]
+    fn test_keypair_blob_round_trip() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let blob = keys.to_bytes();
+        assert_eq!(&blob[..4], &KEYPAIR_MAGIC);
+        assert_eq!(blob[4], KEYPAIR_VERSION);
+        assert_eq!(
+            blob.len(),
+            6 + KYBER_PUBLIC_KEY_BYTES + KYBER_SECRET_KEY_BYTES
+        );
+        assert_eq!(Keypair::from_bytes(&blob).unwrap(), keys);
+
+        let mut bad = blob.clone();
+        bad[0] ^= 1;
+        assert_eq!(
+            Keypair::from_bytes(&bad),
+            Err(KyberLibError::InvalidInput)
+        );
+        let mut bad = blob.clone();
+        bad[4] = KEYPAIR_VERSION + 1;
+        assert_eq!(
+            Keypair::from_bytes(&bad),
+            Err(KyberLibError::InvalidInput)
+        );
+        assert_eq!(
+            Keypair::from_bytes(&blob[..blob.len() - 1]),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // A blob written at another security level is rejected as a key error
+    #[test]
+    fn test_keypair_blob_other_level() {
+        use kyberlib::params::{sizes_for, SecurityLevel};
+        let other = if KYBER_SECURITY_PARAMETER == 4 {
+            SecurityLevel::Kyber512
+        } else {
+            SecurityLevel::Kyber1024
+        };
+        let sizes = sizes_for(other);
+        let mut blob = KEYPAIR_MAGIC.to_vec();
+        blob.push(KEYPAIR_VERSION);
+        blob.push(other.k() as u8);
+        blob.resize(
+            blob.len() + sizes.public_key + sizes.secret_key,
+            7,
+        );
+        assert_eq!(
+            Keypair::from_bytes(&blob),
+            Err(KyberLibError::InvalidKey)
+        );
+    }
+
+    // keypair_batch is deterministic and gives distinct keypairs
+    #[test]
+    fn test_keypair_batch_deterministic() {
+        let master = [11u8; 32];
+        let batch = keypair_batch(master, 3);
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch, keypair_batch(master, 3));
+        assert_eq!(batch[..2], keypair_batch(master, 2)[..]);
+        assert_ne!(batch[0].public, batch[1].public);
+        assert_ne!(batch, keypair_batch([13u8; 32], 3));
+        assert!(keypair_batch(master, 0).is_empty());
+    }
+
+    // keypair_batch items are derive()d from SHAKE256(seed || index)
+    #[cfg(feature = "fips202-export")]
+    #[test]
+    fn test_keypair_batch_matches_derive() {
+        let master = [11u8; 32];
+        let batch = keypair_batch(master, 3);
+        for (i, keys) in batch.iter().enumerate() {
+            let mut input = master.to_vec();
+            input.extend_from_slice(&(i as u64).to_le_bytes());
+            let mut seed = [0u8; 64];
+            sha3::shake256(&mut seed, &input);
+            assert_eq!(*keys, derive(&seed).unwrap());
+        }
+    }
+
+    // The parallel batch is identical to the serial one
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_keypair_batch_par_matches_serial() {
+        let master = [12u8; 32];
+        assert_eq!(
+            keypair_batch_par(master, 64),
+            keypair_batch(master, 64)
+        );
+        assert!(keypair_batch_par(master, 0).is_empty());
+    }
+
+    // secret_key_ct_eq catches differences anywhere in the key
+    #[test]
+    fn test_secret_key_ct_eq() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let copy = keys.secret;
+        assert!(secret_key_ct_eq(&keys.secret, &copy));
+
+        let mut first = keys.secret;
+        first[0] ^= 1;
+        assert!(!secret_key_ct_eq(&keys.secret, &first));
+        let mut last = keys.secret;
+        last[KYBER_SECRET_KEY_BYTES - 1] ^= 0x80;
+        assert!(!secret_key_ct_eq(&keys.secret, &last));
+
+        assert!(!secret_key_ct_eq(
+            &keys.secret,
+            &keys.secret[..KYBER_SECRET_KEY_BYTES - 1]
+        ));
+    }
+
+    // Without re-encryption, honest ciphertexts give the same secret
+    // as decapsulate, but tampered ones are no longer implicitly rejected
+    #[cfg(feature = "fast-decap")]
+    #[test]
+    fn test_decapsulate_no_reencrypt() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        for _ in 0..8 {
+            let (ct, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+            assert_eq!(
+                decapsulate_no_reencrypt(&ct, &keys.secret).unwrap(),
+                decapsulate(&ct, &keys.secret).unwrap()
+            );
+            assert_eq!(
+                decapsulate_no_reencrypt(&ct, &keys.secret).unwrap(),
+                ss
+            );
+        }
+        let (mut ct, _) = encapsulate(&keys.public, &mut rng).unwrap();
+        ct[0] ^= 1;
+        assert_ne!(
+            decapsulate_no_reencrypt(&ct, &keys.secret).unwrap(),
+            decapsulate(&ct, &keys.secret).unwrap()
+        );
+        assert_eq!(
+            decapsulate_no_reencrypt(&ct[1..], &keys.secret),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // public_keys_match for pinned keys, including length mismatches
+    #[test]
+    fn test_public_keys_match() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let other = keypair(&mut OsRng).unwrap();
+        let pinned = keys.public;
+        assert!(public_keys_match(&keys.public, &pinned));
+        assert!(!public_keys_match(&keys.public, &other.public));
+
+        let mut last = pinned;
+        last[KYBER_PUBLIC_KEY_BYTES - 1] ^= 1;
+        assert!(!public_keys_match(&keys.public, &last));
+
+        let longer = [&pinned[..], &[0u8; 384][..]].concat();
+        assert!(!public_keys_match(&pinned, &longer));
+        assert!(!public_keys_match(&pinned[..800], &pinned));
+        assert!(public_keys_match(&[], &[]));
+    }
+
+    // The returned ct_hash is SHA3-256 of the ciphertext
+    #[cfg(not(feature = "90s"))]
+    #[test]
+    fn test_encapsulate_with_ct_hash() {
+        use ::sha3::{Digest, Sha3_256};
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let (ct, ss, ct_hash) =
+            encapsulate_with_ct_hash(&keys.public, &mut rng).unwrap();
+        assert_eq!(ct_hash[..], Sha3_256::digest(ct)[..]);
+        assert_eq!(decapsulate(&ct, &keys.secret).unwrap(), ss);
+        assert_eq!(
+            encapsulate_with_ct_hash(&keys.public[1..], &mut rng),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // Public keys round-trip through base32
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_base32_round_trip() {
+        for _ in 0..4 {
+            let keys = keypair(&mut OsRng).unwrap();
+            let text = encode_base32(&keys.public);
+            assert_eq!(
+                text.len(),
+                (KYBER_PUBLIC_KEY_BYTES * 8).div_ceil(5)
+            );
+            assert!(text.bytes().all(|c| c.is_ascii_uppercase()
+                || (b'2'..=b'7').contains(&c)));
+            assert_eq!(decode_base32(&text).unwrap(), keys.public);
+        }
+        // RFC 4648 test vector prefix
+        let mut pk = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        pk[..6].copy_from_slice(b"foobar");
+        assert!(encode_base32(&pk).starts_with("MZXW6YTBOI"));
+    }
+
+    // Invalid characters, padding and lengths are rejected
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_base32_rejects_invalid() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let text = encode_base32(&keys.public);
+        for bad in ['a', '0', '1', '8', '=', ' '] {
+            let mut chars: Vec<char> = text.chars().collect();
+            chars[10] = bad;
+            let s: String = chars.into_iter().collect();
+            assert_eq!(
+                decode_base32(&s),
+                Err(KyberLibError::InvalidInput)
+            );
+        }
+        assert_eq!(
+            decode_base32(&text[1..]),
+            Err(KyberLibError::InvalidLength)
+        );
+        assert_eq!(
+            decode_base32(&[text.as_str(), "A"].concat()),
+            Err(KyberLibError::InvalidLength)
+        );
+    }
+
+    // DecapsulationContext matches decapsulate, including on rejection
+    #[test]
+    fn test_decapsulation_context() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let ctx = DecapsulationContext::new(&keys.secret);
+        for _ in 0..8 {
+            let (mut ct, ss) =
+                encapsulate(&keys.public, &mut rng).unwrap();
+            assert_eq!(ctx.decapsulate(&ct).unwrap(), ss);
+            ct[7] ^= 1;
+            assert_eq!(
+                ctx.decapsulate(&ct).unwrap(),
+                decapsulate(&ct, &keys.secret).unwrap()
+            );
+        }
+        assert_eq!(
+            ctx.decapsulate(&[0u8; 3]),
+            Err(KyberLibError::InvalidInput)
+        );
+        assert_eq!(format!("{:?}", ctx), "DecapsulationContext(..)");
+    }
+
+    // Binding to an empty context and to [0] gives different secrets
+    #[test]
+    fn test_context_empty_vs_zero_byte() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let keys = derive(&[4u8; 64]).unwrap();
+        let (ct_a, ss_a) = encapsulate_with_context(
+            &keys.public,
+            &[],
+            &mut StdRng::from_seed([1u8; 32]),
+        )
+        .unwrap();
+        let (ct_b, ss_b) = encapsulate_with_context(
+            &keys.public,
+            &[0],
+            &mut StdRng::from_seed([1u8; 32]),
+        )
+        .unwrap();
+        assert_ne!(ss_a, ss_b);
+        assert_eq!(
+            decapsulate_with_context(&ct_a, &keys.secret, &[]).unwrap(),
+            ss_a
+        );
+        assert_eq!(
+            decapsulate_with_context(&ct_b, &keys.secret, &[0])
+                .unwrap(),
+            ss_b
+        );
+        assert_ne!(
+            decapsulate_with_context(&ct_a, &keys.secret, &[0])
+                .unwrap(),
+            ss_a
+        );
+    }
+
+    // Fingerprints are stable for a key and differ between keys
+    #[test]
+    fn test_public_key_fingerprint() {
+        let keys = derive(&[1u8; 64]).unwrap();
+        let fingerprint = public_key_fingerprint(&keys.public);
+        assert_eq!(fingerprint, public_key_fingerprint(&keys.public));
+
+        // First 8 bytes of SHA3-256(pk), colon-separated
+        use ::sha3::{Digest, Sha3_256};
+        let expected: Vec<String> = Sha3_256::digest(keys.public)[..8]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(fingerprint, expected.join(":"));
+
+        let other = derive(&[2u8; 64]).unwrap();
+        assert_ne!(public_key_fingerprint(&other.public), fingerprint);
+        assert_eq!(
+            public_key_fingerprint(&[]),
+            "a7:ff:c6:f8:bf:1e:d7:66"
+        );
+    }
+
+    // Three recipients each recover their own secret from the container
+    #[test]
+    fn test_multi_recipient_ciphertext() {
+        let mut rng = OsRng;
+        let recipients: Vec<Keypair> =
+            (0..3).map(|_| keypair(&mut rng).unwrap()).collect();
+        let pks: Vec<PublicKey> =
+            recipients.iter().map(|keys| keys.public).collect();
+        let (container, secrets) =
+            MultiRecipientCiphertext::encapsulate_to_all(
+                &pks, &mut rng,
+            )
+            .unwrap();
+        assert_eq!(container.len(), 3);
+        assert_eq!(secrets.len(), 3);
+        let bytes = container.as_bytes();
+        assert_eq!(bytes.len(), 4 + 3 * KYBER_CIPHERTEXT_BYTES);
+        assert_eq!(bytes[..4], [0, 0, 0, 3]);
+
+        for (i, keys) in recipients.iter().enumerate() {
+            let ct = MultiRecipientCiphertext::extract_for(bytes, i)
+                .unwrap();
+            assert_eq!(
+                decapsulate(&ct, &keys.secret).unwrap(),
+                secrets[i]
+            );
+            // Another recipient's slice does not give this secret
+            let other = MultiRecipientCiphertext::extract_for(
+                bytes,
+                (i + 1) % 3,
+            )
+            .unwrap();
+            assert_ne!(
+                decapsulate(&other, &keys.secret).unwrap(),
+                secrets[i]
+            );
+        }
+
+        assert_eq!(
+            MultiRecipientCiphertext::extract_for(bytes, 3),
+            Err(KyberLibError::InvalidInput)
+        );
+        assert_eq!(
+            MultiRecipientCiphertext::extract_for(
+                &bytes[..bytes.len() - 1],
+                0
+            ),
+            Err(KyberLibError::InvalidLength)
+        );
+        assert_eq!(
+            MultiRecipientCiphertext::extract_for(&bytes[..3], 0),
+            Err(KyberLibError::InvalidLength)
+        );
+    }
+
+    // Only the length of a ciphertext can make it malformed
+    #[test]
+    fn test_ciphertext_is_well_formed() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let (ct, _) = encapsulate(&keys.public, &mut OsRng).unwrap();
+        assert!(ciphertext_is_well_formed(&ct));
+        assert!(!ciphertext_is_well_formed(&ct[1..]));
+        assert!(!ciphertext_is_well_formed(&[]));
+
+        // Saturated coefficients still decompress into range
+        let garbage = [0xffu8; KYBER_CIPHERTEXT_BYTES];
+        assert!(ciphertext_is_well_formed(&garbage));
+        assert!(decapsulate(&garbage, &keys.secret).is_ok());
+    }
+
+    // HKDF expansion depends only on the secret and info
+    #[cfg(feature = "hkdf")]
+    #[test]
+    fn test_expand_hkdf() {
+        let ss = [0x0bu8; KYBER_SHARED_SECRET_BYTES];
+        let mut a = [0u8; 64];
+        let mut b = [0u8; 64];
+        expand_hkdf(&ss, b"info", &mut a).unwrap();
+        expand_hkdf(&ss, b"info", &mut b).unwrap();
+        assert_eq!(a, b);
+
+        expand_hkdf(&ss, b"other", &mut b).unwrap();
+        assert_ne!(a, b);
+
+        // A shorter output is a prefix of the longer one
+        let mut short = [0u8; 16];
+        expand_hkdf(&ss, b"info", &mut short).unwrap();
+        assert_eq!(short, a[..16]);
+
+        let mut too_long = vec![0u8; 255 * 32 + 1];
+        assert_eq!(
+            expand_hkdf(&ss, b"info", &mut too_long),
+            Err(KyberLibError::InvalidLength)
+        );
+    }
+
+    // Wiping a keypair zeroes both keys while it is still in scope
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_keypair_wipe() {
+        let mut keys = keypair(&mut OsRng).unwrap();
+        keys.wipe();
+        assert!(keys.public.iter().all(|&b| b == 0));
+        assert!(keys.secret.iter().all(|&b| b == 0));
+    }
+
+    // Counters give distinct ciphertexts, a repeated counter the same
+    #[test]
+    fn test_encapsulate_counter() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let seed = [3u8; 32];
+        let (ct0, ss0) =
+            encapsulate_counter(&keys.public, &seed, 0).unwrap();
+        let (ct1, ss1) =
+            encapsulate_counter(&keys.public, &seed, 1).unwrap();
+        assert_ne!(ct0, ct1);
+        assert_ne!(ss0, ss1);
+        assert_eq!(decapsulate(&ct1, &keys.secret).unwrap(), ss1);
+
+        let (again, ss_again) =
+            encapsulate_counter(&keys.public, &seed, 0).unwrap();
+        assert_eq!(again, ct0);
+        assert_eq!(ss_again, ss0);
+
+        assert_eq!(
+            encapsulate_counter(&keys.public[1..], &seed, 0),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // The matrix seed is the tail of the public key
+    #[test]
+    fn test_public_key_seed() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let rho = public_key_seed(&keys.public);
+        assert_eq!(rho[..], keys.public[KYBER_PUBLIC_KEY_BYTES - 32..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong public key length")]
+    fn test_public_key_seed_wrong_length() {
+        let _ = public_key_seed(&[0u8; 32]);
+    }
+
+    // derive takes exactly KYBER_DERIVE_SEED_BYTES of seed
+    #[test]
+    fn test_derive_seed_length() {
+        let seed = [5u8; KYBER_DERIVE_SEED_BYTES];
+        assert!(derive(&seed).is_ok());
+        assert_eq!(
+            derive(&seed[..KYBER_DERIVE_SEED_BYTES - 1]),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // The wrapped secret is the one plain encapsulation would give
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_encapsulate_zeroizing() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let keys = keypair(&mut OsRng).unwrap();
+        let (ct, ss) = encapsulate(
+            &keys.public,
+            &mut StdRng::from_seed([9u8; 32]),
+        )
+        .unwrap();
+        let (wrapped_ct, wrapped_ss) = encapsulate_zeroizing(
+            &keys.public,
+            &mut StdRng::from_seed([9u8; 32]),
+        )
+        .unwrap();
+        assert_eq!(wrapped_ct, ct);
+        assert_eq!(*wrapped_ss, ss);
+        assert_eq!(
+            encapsulate_zeroizing(&keys.public[1..], &mut OsRng)
+                .map(|_| ()),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    // Strict decapsulation rejects only the all-zero secret key
+    #[test]
+    fn test_decapsulate_strict() {
+        let keys = keypair(&mut OsRng).unwrap();
+        let (ct, ss) = encapsulate(&keys.public, &mut OsRng).unwrap();
+        assert_eq!(decapsulate_strict(&ct, &keys.secret).unwrap(), ss);
+
+        let zero = [0u8; KYBER_SECRET_KEY_BYTES];
+        assert!(decapsulate(&ct, &zero).is_ok());
+        assert_eq!(
+            decapsulate_strict(&ct, &zero),
+            Err(KyberLibError::InvalidKey)
+        );
+        assert_eq!(
+            decapsulate_strict(&ct[1..], &keys.secret),
+            Err(KyberLibError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_encapsulate_split() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let (ct, handle) =
+            encapsulate_split(&keys.public, &mut rng).unwrap();
+        // The handle hides the secret until it is extracted
+        assert_eq!(format!("{:?}", handle), "SharedSecretHandle(..)");
+        assert_eq!(
+            handle.extract(),
+            decapsulate(&ct, &keys.secret).unwrap()
+        );
+        assert!(matches!(
+            encapsulate_split(&keys.public[1..], &mut rng),
+            Err(KyberLibError::InvalidInput)
+        ));
+    }
+
+    // The reader streams SHAKE256(ss)
+    #[test]
+    fn test_xof_reader_matches_one_shot() {
+        use ::sha3::digest::{ExtendableOutput, Update, XofReader};
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let (_, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+
+        let mut expected = [0u8; 64];
+        let mut shake = ::sha3::Shake256::default();
+        shake.update(&ss);
+        shake.finalize_xof().read(&mut expected);
+        let mut reader = xof_reader(&ss);
+        let mut first = [0u8; 64];
+        reader.read(&mut first[..10]);
+        reader.read(&mut first[10..]);
+        assert_eq!(first, expected);
+    }
+
+    // Byte-wise pulls continue the same stream across the rate
+    #[test]
+    fn test_xof_reader_iterator_matches_read() {
+        let ss = [7u8; KYBER_SHARED_SECRET_BYTES];
+        let mut long = [0u8; 400];
+        xof_reader(&ss).read(&mut long);
+        let streamed: Vec<u8> = xof_reader(&ss).take(400).collect();
+        assert_eq!(&streamed[..], &long[..]);
+        assert_ne!(
+            long[..64],
+            xof_reader(&[8u8; 32]).take(64).collect::<Vec<_>>()[..]
+        );
+    }
+
+    #[test]
+    fn test_ciphertexts_equal() {
+        let mut rng = OsRng;
+        let keys = keypair(&mut rng).unwrap();
+        let (ct, _) = encapsulate(&keys.public, &mut rng).unwrap();
+        let cached = ct;
+        assert!(ciphertexts_equal(&ct, &cached));
+
+        // A difference at the last byte is caught
+        let mut last = ct;
+        last[KYBER_CIPHERTEXT_BYTES - 1] ^= 1;
+        assert!(!ciphertexts_equal(&ct, &last));
+
+        // Length mismatches never match, even on a common prefix
+        assert!(!ciphertexts_equal(
+            &ct,
+            &ct[..KYBER_CIPHERTEXT_BYTES - 1]
+        ));
+        assert!(!ciphertexts_equal(&ct, &[]));
+    }
 }
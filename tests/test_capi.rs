@@ -0,0 +1,96 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "capi")]
+
+use kyberlib::capi::*;
+use std::ptr;
+
+// A full keypair/encapsulate/decapsulate round-trip through the C ABI
+#[test]
+fn test_capi_round_trip() {
+    let mut pk = vec![0u8; kyberlib_public_key_bytes()];
+    let mut sk = vec![0u8; kyberlib_secret_key_bytes()];
+    let mut ct = vec![0u8; kyberlib_ciphertext_bytes()];
+    let mut ss1 = vec![0u8; kyberlib_shared_secret_bytes()];
+    let mut ss2 = vec![0u8; kyberlib_shared_secret_bytes()];
+    unsafe {
+        assert_eq!(
+            kyberlib_keypair(
+                pk.as_mut_ptr(),
+                pk.len(),
+                sk.as_mut_ptr(),
+                sk.len()
+            ),
+            KYBERLIB_OK
+        );
+        assert_eq!(
+            kyberlib_encapsulate(
+                pk.as_ptr(),
+                pk.len(),
+                ct.as_mut_ptr(),
+                ct.len(),
+                ss1.as_mut_ptr(),
+                ss1.len()
+            ),
+            KYBERLIB_OK
+        );
+        assert_eq!(
+            kyberlib_decapsulate(
+                ct.as_ptr(),
+                ct.len(),
+                sk.as_ptr(),
+                sk.len(),
+                ss2.as_mut_ptr(),
+                ss2.len()
+            ),
+            KYBERLIB_OK
+        );
+    }
+    assert_ne!(ss1, vec![0u8; ss1.len()]);
+    assert_eq!(ss1, ss2);
+}
+
+// Null pointers and wrong lengths are rejected without writing output
+#[test]
+fn test_capi_invalid_arguments() {
+    let mut sk = vec![0u8; kyberlib_secret_key_bytes()];
+    let mut ct = vec![0u8; kyberlib_ciphertext_bytes()];
+    let mut ss = vec![0u8; kyberlib_shared_secret_bytes()];
+    unsafe {
+        assert_eq!(
+            kyberlib_keypair(
+                ptr::null_mut(),
+                kyberlib_public_key_bytes(),
+                sk.as_mut_ptr(),
+                sk.len()
+            ),
+            KYBERLIB_ERR_INVALID_INPUT
+        );
+        let short_pk = vec![0u8; kyberlib_public_key_bytes() - 1];
+        assert_eq!(
+            kyberlib_encapsulate(
+                short_pk.as_ptr(),
+                short_pk.len(),
+                ct.as_mut_ptr(),
+                ct.len(),
+                ss.as_mut_ptr(),
+                ss.len()
+            ),
+            KYBERLIB_ERR_INVALID_INPUT
+        );
+        assert_eq!(
+            kyberlib_decapsulate(
+                ct.as_ptr(),
+                ct.len(),
+                sk.as_ptr(),
+                sk.len(),
+                ss.as_mut_ptr(),
+                ss.len() + 1
+            ),
+            KYBERLIB_ERR_INVALID_INPUT
+        );
+    }
+    assert_eq!(sk, vec![0u8; sk.len()]);
+    assert_eq!(ss, vec![0u8; ss.len()]);
+}
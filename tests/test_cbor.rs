@@ -0,0 +1,46 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "cbor")]
+
+use kyberlib::cbor::*;
+use kyberlib::*;
+
+#[test]
+fn test_keypair_cbor_round_trip() {
+    let keys = keypair(&mut rand::thread_rng()).unwrap();
+    let bytes = keypair_to_cbor(&keys);
+    // Map header plus two keys and two byte string headers
+    let raw = KYBER_PUBLIC_KEY_BYTES + KYBER_SECRET_KEY_BYTES;
+    assert!(bytes.len() > raw && bytes.len() <= raw + 16);
+    assert_eq!(keypair_from_cbor(&bytes), Ok(keys));
+}
+
+#[test]
+fn test_ake_init_cbor_round_trip() {
+    let mut rng = rand::thread_rng();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let mut alice = Ake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let msg = HandshakeMessage::AkeInit(init);
+    let bytes = message_to_cbor(&msg);
+    assert!(bytes.len() > AKE_INIT_BYTES);
+    assert!(bytes.len() <= AKE_INIT_BYTES + 16);
+    assert_eq!(message_from_cbor(&bytes), Ok(msg));
+}
+
+#[test]
+fn test_cbor_rejects_malformed() {
+    let keys = keypair(&mut rand::thread_rng()).unwrap();
+    let bytes = keypair_to_cbor(&keys);
+    assert_eq!(
+        keypair_from_cbor(&bytes[..bytes.len() - 1]),
+        Err(KyberLibError::InvalidInput)
+    );
+    // A keypair map decodes as a message with kind 1 but the wrong
+    // payload type
+    assert_eq!(
+        message_from_cbor(&bytes),
+        Err(KyberLibError::InvalidInput)
+    );
+}
@@ -29,6 +29,13 @@ fn test_kyber_lib_error_display() {
         "Random bytes generation function failed"
     );
 
+    let error =
+        KyberLibError::RandomBytesGenerationSized { requested: 32 };
+    assert_eq!(
+        error.to_string(),
+        "Random bytes generation function failed for 32 bytes"
+    );
+
     let error = KyberLibError::InvalidKey;
     assert_eq!(
         error.to_string(),
@@ -39,6 +46,21 @@ fn test_kyber_lib_error_display() {
         error.to_string(),
         "The length of the input buffer is invalid."
     );
+    let error = KyberLibError::HandshakeOutOfOrder;
+    assert_eq!(
+        error.to_string(),
+        "Key exchange step called out of order."
+    );
+    let error = KyberLibError::HandshakeInProgress;
+    assert_eq!(
+        error.to_string(),
+        "A key exchange is already in progress."
+    );
+    let error = KyberLibError::SelfTestFailure;
+    assert_eq!(
+        error.to_string(),
+        "Self-test produced an unexpected shared secret."
+    );
 }
 
 #[test]
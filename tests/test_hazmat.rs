@@ -0,0 +1,149 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(any(feature = "debug-internals", feature = "hazmat"))]
+
+use kyberlib::{hazmat::*, params::*, *};
+#[cfg(feature = "hazmat")]
+use rand_core::{CryptoRng, RngCore};
+
+// Re-encoding decoded coefficients reproduces the public key bytes
+#[cfg(feature = "debug-internals")]
+#[test]
+fn decode_encode_public_poly_roundtrip() {
+    let mut rng = rand::thread_rng();
+    let keys = keypair(&mut rng).unwrap();
+    let coeffs = decode_public_poly(&keys.public);
+    assert_eq!(coeffs.len(), KYBER_SECURITY_PARAMETER * KYBER_N);
+    assert!(coeffs.iter().all(|&c| (0..KYBER_Q as i16).contains(&c)));
+
+    let mut seed = [0u8; KYBER_SYM_BYTES];
+    seed.copy_from_slice(&keys.public[KYBER_POLYVEC_BYTES..]);
+    assert_eq!(
+        encode_public_poly(&coeffs, &seed).unwrap(),
+        keys.public
+    );
+    assert_eq!(
+        encode_public_poly(&coeffs[1..], &seed),
+        Err(KyberLibError::InvalidLength)
+    );
+}
+
+// The audited intermediates are the two halves of the seed
+#[cfg(feature = "debug-internals")]
+#[test]
+fn derive_with_intermediates_matches_derive() {
+    let mut seed = [0u8; 64];
+    for (i, b) in seed.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    let (keys, d, z) = derive_with_intermediates(&seed);
+    assert_eq!(d[..], seed[..32]);
+    assert_eq!(z[..], seed[32..]);
+    assert_eq!(keys, derive(&seed).unwrap());
+}
+
+// Keygen counts at least one iteration per two coefficients, and each
+// expansion starts from zero rather than adding to the last count
+#[cfg(feature = "debug-internals")]
+#[test]
+fn last_rejection_count_resets_per_keygen() {
+    let min = KYBER_SECURITY_PARAMETER * KYBER_SECURITY_PARAMETER * 128;
+    let _ = derive(&[1u8; 64]).unwrap();
+    let first = last_rejection_count();
+    assert!(first >= min);
+    let _ = derive(&[2u8; 64]).unwrap();
+    let _ = derive(&[1u8; 64]).unwrap();
+    assert_eq!(last_rejection_count(), first);
+}
+
+// Yields the same byte forever, so the encryptor's message is known
+#[cfg(feature = "hazmat")]
+#[derive(Debug)]
+struct ConstRng(u8);
+
+#[cfg(feature = "hazmat")]
+impl RngCore for ConstRng {
+    fn next_u32(&mut self) -> u32 {
+        u32::from_ne_bytes([self.0; 4])
+    }
+    fn next_u64(&mut self) -> u64 {
+        u64::from_ne_bytes([self.0; 8])
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.iter_mut().for_each(|b| *b = self.0);
+    }
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hazmat")]
+impl CryptoRng for ConstRng {}
+
+// IND-CPA decryption recovers the message the encryptor hashed
+// from its random bytes
+#[cfg(feature = "hazmat")]
+#[test]
+fn decapsulate_cpa_recovers_message() {
+    let keys = keypair(&mut rand::thread_rng()).unwrap();
+    let (ct, _) =
+        encapsulate(&keys.public, &mut ConstRng(0x42)).unwrap();
+
+    let mut expected = [0u8; KYBER_SYM_BYTES];
+    symmetric::hash_h(
+        &mut expected,
+        &[0x42; KYBER_SYM_BYTES],
+        KYBER_SYM_BYTES,
+    )
+    .unwrap();
+    assert_eq!(decapsulate_cpa(&ct, &keys.secret), expected);
+}
+
+// cpa_encrypt/cpa_decrypt round-trip, and the coins select the ciphertext
+#[cfg(feature = "hazmat")]
+#[test]
+fn test_cpa_encrypt_decrypt() {
+    let mut rng = rand::thread_rng();
+    let keys = keypair(&mut rng).unwrap();
+    for _ in 0..8 {
+        let mut msg = [0u8; KYBER_SYM_BYTES];
+        let mut coins = [0u8; KYBER_SYM_BYTES];
+        rng.fill_bytes(&mut msg);
+        rng.fill_bytes(&mut coins);
+        let ct = cpa_encrypt(&keys.public, &msg, &coins);
+        assert_eq!(cpa_decrypt(&keys.secret, &ct), msg);
+        assert_eq!(cpa_encrypt(&keys.public, &msg, &coins), ct);
+
+        let mut other = coins;
+        other[0] ^= 1;
+        let ct2 = cpa_encrypt(&keys.public, &msg, &other);
+        assert_ne!(ct2[..], ct[..]);
+        assert_eq!(cpa_decrypt(&keys.secret, &ct2), msg);
+    }
+}
+
+// ct_select only copies when the condition is nonzero
+#[cfg(feature = "hazmat")]
+#[test]
+fn test_ct_select() {
+    for len in [0usize, 1, 7, 32, 1000] {
+        let src: Vec<u8> = (0..len).map(|i| i as u8 ^ 0xA5).collect();
+        let original: Vec<u8> = (0..len).map(|i| i as u8).collect();
+
+        let mut dst = original.clone();
+        ct_select(&mut dst, &src, 0);
+        assert_eq!(dst, original);
+
+        ct_select(&mut dst, &src, 0xFF);
+        assert_eq!(dst, src);
+
+        let mut dst = original.clone();
+        ct_select(&mut dst, &src, 1);
+        assert_eq!(dst, src);
+    }
+}
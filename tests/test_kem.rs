@@ -16,9 +16,11 @@ fn keypair_encap_decap() {
 fn keypair_import_fake() {
     let mut rng = rand::thread_rng();
     let mut keys = keypair(&mut rng).unwrap();
+    // keypairfrom wipes its inputs when zeroize is enabled
+    let public = keys.public;
     let key = keypairfrom(&mut keys.public, &mut keys.secret, &mut rng)
         .unwrap();
-    assert_eq!(keys.public, key.public);
+    assert_eq!(public, key.public);
 }
 
 #[test]
@@ -64,7 +66,9 @@ fn keypair_failed_randombytes() {
     let mut rng = FailingRng::default();
     assert_eq!(
         keypair(&mut rng),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     );
 }
 
@@ -75,7 +79,9 @@ fn keypair_encap_failed_randombytes() {
     let mut rng = FailingRng::default();
     assert_eq!(
         encapsulate(&keys.public, &mut rng),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     );
 }
 
@@ -86,3 +92,65 @@ fn public_from_private() {
     let pk2 = public(&keys.secret);
     assert_eq!(pk2, keys.public);
 }
+
+// TurboSHAKE builds still round-trip keygen, encapsulation and decapsulation
+#[cfg(feature = "turboshake")]
+#[test]
+fn turboshake_keypair_encap_decap() {
+    let mut rng = rand::thread_rng();
+    assert_eq!(symmetric::symmetric_backend(), "turboshake");
+    let keys = keypair(&mut rng).unwrap();
+    let (ct, ss1) = encapsulate(&keys.public, &mut rng).unwrap();
+    let ss2 = decapsulate(&ct, &keys.secret).unwrap();
+    assert_eq!(ss1, ss2);
+}
+
+// The coins, pre-key and message hash are wiped before encapsulation
+// returns under `zeroize`, without changing its output
+#[cfg(all(
+    feature = "zeroize",
+    not(any(
+        feature = "kyber512",
+        feature = "kyber1024",
+        feature = "90s",
+        feature = "turboshake"
+    ))
+))]
+#[test]
+fn encapsulate_output_unchanged_by_zeroize() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::from_seed([42u8; 32]);
+    let keys = keypair(&mut rng).unwrap();
+    let (ct, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+    assert_eq!(decapsulate(&ct, &keys.secret).unwrap(), ss);
+
+    let mut transcript = ct.to_vec();
+    transcript.extend_from_slice(&ss);
+    let mut digest = [0u8; 32];
+    symmetric::hash_h(&mut digest, &transcript, transcript.len())
+        .unwrap();
+    let hex: String =
+        digest.iter().map(|b| format!("{:02x}", b)).collect();
+    // SHA3-256 of ct || ss from a build without `zeroize`
+    assert_eq!(
+        hex,
+        "ed1920e1eb00c2397581f70ccf58c29113347589fa7835100bb641b10a312ed9"
+    );
+}
+
+// The unchecked decapsulation asserts the ciphertext length in debug
+// builds instead of silently ignoring the extra bytes
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "ciphertext length mismatch")]
+fn decrypt_message_wrong_ct_length_caught() {
+    let mut rng = rand::thread_rng();
+    let keys = keypair(&mut rng).unwrap();
+    let (ct, _) = encapsulate(&keys.public, &mut rng).unwrap();
+    let mut long_ct = ct.to_vec();
+    long_ct.push(0);
+    let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+    kem::decrypt_message(&mut ss, &long_ct, &keys.secret);
+}
@@ -192,7 +192,9 @@ fn ake_uake_failed_randombytes_keypair() {
     let mut rng = FailingRng::default();
     assert_eq!(
         keypair(&mut rng),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     );
 }
 
@@ -205,7 +207,9 @@ fn uake_failed_randombytes_client_init() {
     let mut rng = FailingRng::default();
     assert_eq!(
         alice.client_init(&bob_keys.public, &mut rng),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     );
 }
 
@@ -221,7 +225,9 @@ fn uake_failed_randombytes_server_receive() {
     let mut rng = FailingRng::default();
     assert_eq!(
         bob.server_receive(client_init, &bob_keys.secret, &mut rng),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     );
 }
 
@@ -233,7 +239,9 @@ fn ake_failed_randombytes_client_init() {
     let mut rng = FailingRng::default();
     assert_eq!(
         alice.client_init(&bob_keys.public, &mut rng),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     );
 }
 
@@ -254,6 +262,810 @@ fn ake_failed_randombytes_server_receive() {
             &bob_keys.secret,
             &mut rng
         ),
-        Err(KyberLibError::RandomBytesGeneration)
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
     )
 }
+
+// Handshake state transitions for an in-order UAKE
+#[test]
+fn uake_state_transitions() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let bob_keys = keypair(&mut rng).unwrap();
+    assert_eq!(alice.state(), HandshakeState::Init);
+    assert_eq!(bob.state(), HandshakeState::Init);
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(alice.state(), HandshakeState::AwaitingResponse);
+    let server_send = bob
+        .server_receive(client_init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    assert_eq!(bob.state(), HandshakeState::Confirmed);
+    alice.client_confirm(server_send).unwrap();
+    assert_eq!(alice.state(), HandshakeState::Confirmed);
+}
+
+// Confirming a UAKE before initiating it, or twice, is an error
+#[test]
+fn uake_out_of_order() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let bob_keys = keypair(&mut rng).unwrap();
+    assert_eq!(
+        alice.client_confirm([0u8; UAKE_RESPONSE_BYTES]),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+    assert_eq!(alice.state(), HandshakeState::Init);
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(
+        alice.server_receive(client_init, &bob_keys.secret, &mut rng),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+    let server_send = bob
+        .server_receive(client_init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    alice.client_confirm(server_send).unwrap();
+    assert_eq!(
+        alice.client_confirm(server_send),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+}
+
+// A second client_init before confirming the first is rejected and
+// leaves the pending exchange usable
+#[test]
+fn client_init_while_in_progress() {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(
+        alice.client_init(&bob_keys.public, &mut rng),
+        Err(KyberLibError::HandshakeInProgress)
+    );
+    let response = bob
+        .server_receive(init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    alice.client_confirm(response).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+    assert!(alice.client_init(&bob_keys.public, &mut rng).is_ok());
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(
+        alice.client_init(&bob_keys.public, &mut rng),
+        Err(KyberLibError::HandshakeInProgress)
+    );
+    let response = bob
+        .server_receive(
+            init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    alice.client_confirm(response, &alice_keys.secret).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+}
+
+// Handshake state transitions for an in-order AKE
+#[test]
+fn ake_state_transitions() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    assert_eq!(alice.state(), HandshakeState::Init);
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(alice.state(), HandshakeState::AwaitingResponse);
+    let server_send = bob
+        .server_receive(
+            client_init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    assert_eq!(bob.state(), HandshakeState::Confirmed);
+    alice
+        .client_confirm(server_send, &alice_keys.secret)
+        .unwrap();
+    assert_eq!(alice.state(), HandshakeState::Confirmed);
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+}
+
+// Confirming an AKE before initiating it is an error
+#[test]
+fn ake_out_of_order() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Ake::new();
+    let alice_keys = keypair(&mut rng).unwrap();
+    assert_eq!(
+        alice.client_confirm(
+            [0u8; AKE_RESPONSE_BYTES],
+            &alice_keys.secret
+        ),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+    assert_eq!(alice.state(), HandshakeState::Init);
+}
+
+// Honest AKE parties derive the same channel-binding value
+#[test]
+fn ake_channel_binding() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let server_send = bob
+        .server_receive(
+            client_init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    alice
+        .client_confirm(server_send, &alice_keys.secret)
+        .unwrap();
+    assert_eq!(alice.channel_binding(), bob.channel_binding());
+    assert_ne!(alice.channel_binding().unwrap(), [0u8; 32]);
+}
+
+// The channel binding is only available once the exchange completes
+#[test]
+fn ake_channel_binding_before_confirmed() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Ake::new();
+    let bob_keys = keypair(&mut rng).unwrap();
+    assert_eq!(
+        alice.channel_binding(),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+    let _ = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(
+        alice.channel_binding(),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+}
+
+// Tampering with Bob's response changes Alice's channel binding
+#[test]
+fn ake_channel_binding_tampered_server_send() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let mut server_send = bob
+        .server_receive(
+            client_init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    server_send[..4].copy_from_slice(&[255u8; 4]);
+    alice
+        .client_confirm(server_send, &alice_keys.secret)
+        .unwrap();
+    assert_ne!(
+        alice.channel_binding().unwrap(),
+        bob.channel_binding().unwrap()
+    );
+}
+
+// Public keys index a BTreeMap and display as lowercase hex
+#[test]
+fn public_key_id_as_map_key() {
+    use std::collections::BTreeMap;
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let alice_id = PublicKeyId::from(alice_keys.public);
+    let bob_id = PublicKeyId::from(bob_keys.public);
+    assert_ne!(alice_id, bob_id);
+
+    let mut sessions = BTreeMap::new();
+    assert!(sessions.insert(alice_id, "alice").is_none());
+    assert!(sessions.insert(bob_id, "bob").is_none());
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(
+        sessions[&PublicKeyId::from(alice_keys.public)],
+        "alice"
+    );
+    assert_eq!(sessions[&PublicKeyId::from(bob_keys.public)], "bob");
+
+    let mut public = [0u8; KYBER_PUBLIC_KEY_BYTES];
+    public[..3].copy_from_slice(&[0x00, 0xab, 0x0f]);
+    let hex = PublicKeyId::from(public).to_string();
+    assert_eq!(hex.len(), 2 * KYBER_PUBLIC_KEY_BYTES);
+    assert!(hex.starts_with("00ab0f00"));
+}
+
+// with_rng pre-seeds distinct ephemerals and still completes an exchange
+#[test]
+fn uake_with_rng_seeds_ephemerals() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Uake::with_rng(&mut rng).unwrap();
+    let mut bob = Uake::with_rng(&mut rng).unwrap();
+    assert_ne!(alice.temp_key, [0u8; KYBER_SHARED_SECRET_BYTES]);
+    assert_ne!(alice.eska, [0u8; KYBER_SECRET_KEY_BYTES]);
+    assert_ne!(alice.temp_key, bob.temp_key);
+    assert_ne!(alice.eska, bob.eska);
+    assert_eq!(alice.state(), HandshakeState::Init);
+
+    let bob_keys = keypair(&mut rng).unwrap();
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let server_send = bob
+        .server_receive(client_init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    alice.client_confirm(server_send).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+}
+
+// with_rng pre-seeds distinct ephemerals and still completes an exchange
+#[test]
+fn ake_with_rng_seeds_ephemerals() {
+    let mut rng = rand::thread_rng();
+    let mut alice = Ake::with_rng(&mut rng).unwrap();
+    let mut bob = Ake::with_rng(&mut rng).unwrap();
+    assert_ne!(alice.temp_key, bob.temp_key);
+    assert_ne!(alice.eska, bob.eska);
+
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let client_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let server_send = bob
+        .server_receive(
+            client_init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    alice
+        .client_confirm(server_send, &alice_keys.secret)
+        .unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+}
+
+// with_rng surfaces RNG failures
+#[test]
+fn with_rng_failing_rng() {
+    assert_eq!(
+        Uake::with_rng(&mut FailingRng::default()),
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
+    );
+}
+
+// A correctly sized slice becomes a Ciphertext usable by decapsulate
+#[test]
+fn ciphertext_try_from_slice() {
+    let mut rng = rand::thread_rng();
+    let keys = keypair(&mut rng).unwrap();
+    let (ct, ss) = encapsulate(&keys.public, &mut rng).unwrap();
+    let ciphertext = Ciphertext::try_from_slice(&ct[..]).unwrap();
+    assert_eq!(ciphertext.as_bytes(), &ct);
+    assert_eq!(ciphertext, Ciphertext::from(ct));
+    assert_eq!(decapsulate(&ciphertext, &keys.secret).unwrap(), ss);
+}
+
+// A short slice is rejected
+#[test]
+fn ciphertext_try_from_short_slice() {
+    let short = [0u8; KYBER_CIPHERTEXT_BYTES - 1];
+    assert_eq!(
+        Ciphertext::try_from_slice(&short),
+        Err(KyberLibError::InvalidInput)
+    );
+}
+
+#[test]
+fn test_handshake_message_round_trip() {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let uake_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let uake_response = bob
+        .server_receive(uake_init, &bob_keys.secret, &mut rng)
+        .unwrap();
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let ake_init =
+        alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let ake_response = bob
+        .server_receive(
+            ake_init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+
+    for msg in [
+        HandshakeMessage::UakeInit(uake_init),
+        HandshakeMessage::UakeResponse(uake_response),
+        HandshakeMessage::AkeInit(ake_init),
+        HandshakeMessage::AkeResponse(ake_response),
+    ]
+    .iter()
+    {
+        let bytes = msg.to_bytes();
+        assert_eq!(HandshakeMessage::from_bytes(&bytes), Ok(*msg));
+    }
+}
+
+#[test]
+fn test_handshake_message_rejects_wrong_length() {
+    // An AkeResponse frame carrying a UakeResponse-sized payload
+    let bytes = frame::frame_message(
+        frame::FRAME_AKE_RESPONSE,
+        &[0u8; UAKE_RESPONSE_BYTES],
+    );
+    assert_eq!(
+        HandshakeMessage::from_bytes(&bytes),
+        Err(KyberLibError::InvalidLength)
+    );
+}
+
+#[test]
+fn test_response_len() {
+    assert_eq!(response_len(HandshakeKind::Uake), UAKE_RESPONSE_BYTES);
+    assert_eq!(response_len(HandshakeKind::Ake), AKE_RESPONSE_BYTES);
+    assert_eq!(
+        response_len(HandshakeKind::Ake),
+        2 * response_len(HandshakeKind::Uake)
+    );
+}
+
+#[test]
+fn test_uake_into_matches_struct_transcript() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let bob_keys = keypair(&mut StdRng::from_seed([1u8; 32])).unwrap();
+
+    // Struct-based exchange
+    let mut client_rng = StdRng::from_seed([2u8; 32]);
+    let mut server_rng = StdRng::from_seed([3u8; 32]);
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let init = alice
+        .client_init(&bob_keys.public, &mut client_rng)
+        .unwrap();
+    let response = bob
+        .server_receive(init, &bob_keys.secret, &mut server_rng)
+        .unwrap();
+    alice.client_confirm(response).unwrap();
+
+    // Buffer-based exchange under the same seeds
+    let mut client_rng = StdRng::from_seed([2u8; 32]);
+    let mut server_rng = StdRng::from_seed([3u8; 32]);
+    let mut state = UakeState::new();
+    let mut send_a = [0u8; UAKE_INIT_BYTES];
+    let mut send_b = [0u8; UAKE_RESPONSE_BYTES];
+    let mut client_secret = [0u8; KYBER_SHARED_SECRET_BYTES];
+    let mut server_secret = [0u8; KYBER_SHARED_SECRET_BYTES];
+    uake_client_init_into(
+        &mut send_a,
+        &mut state,
+        &bob_keys.public,
+        &mut client_rng,
+    )
+    .unwrap();
+    uake_server_receive_into(
+        &mut send_b,
+        &mut server_secret,
+        &send_a,
+        &bob_keys.secret,
+        &mut server_rng,
+    )
+    .unwrap();
+    uake_client_confirm_into(&mut client_secret, &mut state, &send_b)
+        .unwrap();
+
+    assert_eq!(send_a, init);
+    assert_eq!(send_b, response);
+    assert_eq!(client_secret, alice.shared_secret);
+    assert_eq!(server_secret, bob.shared_secret);
+    assert_eq!(state.state(), HandshakeState::Confirmed);
+    assert_eq!(
+        uake_client_confirm_into(
+            &mut client_secret,
+            &mut state,
+            &send_b
+        ),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+}
+
+#[test]
+fn test_ake_response_well_formed() {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let mallory_keys = keypair(&mut rng).unwrap();
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let response = bob
+        .server_receive(
+            init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    assert!(alice.response_well_formed(&response, &bob_keys.public));
+    assert!(
+        !alice.response_well_formed(&response, &mallory_keys.public)
+    );
+
+    let mut tampered = response;
+    tampered[0] ^= 1;
+    assert!(!alice.response_well_formed(&tampered, &bob_keys.public));
+
+    alice.client_confirm(response, &alice_keys.secret).unwrap();
+    assert!(!alice.response_well_formed(&response, &bob_keys.public));
+}
+
+#[test]
+fn test_ake_response_well_formed_rejects_wrong_server_key() {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let mallory_keys = keypair(&mut rng).unwrap();
+
+    // Alice's initiation reaches Mallory, who answers with her own key
+    let mut alice = Ake::new();
+    let mut mallory = Ake::new();
+    let init =
+        alice.client_init(&mallory_keys.public, &mut rng).unwrap();
+    let response = mallory
+        .server_receive(
+            init,
+            &alice_keys.public,
+            &mallory_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    assert!(!alice.response_well_formed(&response, &bob_keys.public));
+}
+
+#[test]
+fn test_handshake_total_bytes() {
+    assert_eq!(
+        uake_total_bytes(),
+        UAKE_INIT_BYTES + UAKE_RESPONSE_BYTES
+    );
+    assert_eq!(ake_total_bytes(), AKE_INIT_BYTES + AKE_RESPONSE_BYTES);
+    assert_eq!(
+        uake_total_bytes(),
+        KYBER_PUBLIC_KEY_BYTES + 2 * KYBER_CIPHERTEXT_BYTES
+    );
+    assert_eq!(
+        ake_total_bytes(),
+        KYBER_PUBLIC_KEY_BYTES + 3 * KYBER_CIPHERTEXT_BYTES
+    );
+}
+
+// Runs an Ake exchange between two builders and returns both secrets
+fn ake_with_builders(
+    alice: AkeBuilder,
+    bob: AkeBuilder,
+) -> (SharedSecret, SharedSecret) {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let mut alice = alice.build();
+    let mut bob = bob.build();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let response = bob
+        .server_receive(
+            init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    alice.client_confirm(response, &alice_keys.secret).unwrap();
+    (alice.shared_secret, bob.shared_secret)
+}
+
+#[test]
+fn test_ake_builder_context() {
+    let session = AkeBuilder::new().context(b"session 1");
+    let (alice, bob) = ake_with_builders(session, session);
+    assert_eq!(alice, bob);
+
+    let (alice, bob) = ake_with_builders(
+        session,
+        AkeBuilder::new().context(b"session 2"),
+    );
+    assert_ne!(alice, bob);
+
+    let (alice, bob) = ake_with_builders(session, AkeBuilder::new());
+    assert_ne!(alice, bob);
+}
+
+#[test]
+fn test_builder_strict_length_check() {
+    let mut rng = rand::thread_rng();
+    let zero_key = [0u8; KYBER_PUBLIC_KEY_BYTES];
+
+    let mut strict =
+        UakeBuilder::new().strict_length_check(true).build();
+    assert_eq!(
+        strict.client_init(&zero_key, &mut rng),
+        Err(KyberLibError::InvalidKey)
+    );
+    assert_eq!(strict.state(), HandshakeState::Init);
+
+    let mut strict =
+        AkeBuilder::new().strict_length_check(true).build();
+    assert_eq!(
+        strict.client_init(&zero_key, &mut rng),
+        Err(KyberLibError::InvalidKey)
+    );
+
+    // Without the flag the key is used as given
+    let mut lax = UakeBuilder::new().build();
+    assert!(lax.client_init(&zero_key, &mut rng).is_ok());
+}
+
+// Wiping intermediates under `zeroize` leaves every public output of a
+// seeded KEM, UAKE and AKE run unchanged
+#[cfg(all(
+    feature = "zeroize",
+    not(any(
+        feature = "kyber512",
+        feature = "kyber1024",
+        feature = "90s",
+        feature = "turboshake"
+    ))
+))]
+#[test]
+fn test_zeroize_outputs_unchanged() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::from_seed([7u8; 32]);
+    let mut transcript = Vec::new();
+
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let (ct, ss) = encapsulate(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(decapsulate(&ct, &bob_keys.secret).unwrap(), ss);
+    transcript.extend_from_slice(&ct);
+    transcript.extend_from_slice(&ss);
+
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let response = bob
+        .server_receive(init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    alice.client_confirm(response).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+    transcript.extend_from_slice(&init);
+    transcript.extend_from_slice(&response);
+    transcript.extend_from_slice(&alice.shared_secret);
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let response = bob
+        .server_receive(
+            init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    alice.client_confirm(response, &alice_keys.secret).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+    transcript.extend_from_slice(&init);
+    transcript.extend_from_slice(&response);
+    transcript.extend_from_slice(&alice.shared_secret);
+
+    // SHA3-256 of the transcript from a build without `zeroize`
+    let mut digest = [0u8; 32];
+    symmetric::hash_h(&mut digest, &transcript, transcript.len())
+        .unwrap();
+    let hex: String =
+        digest.iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(
+        hex,
+        "f13ea6c757c230a627d4060a958ead97bff7201daba6c8df3b75a4fdbf8e1e3b"
+    );
+}
+
+// A handshake saved after client_init can be confirmed by a fresh
+// struct restored from the serialized state
+#[test]
+fn save_restore_state_resumes_handshake() {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let saved = alice.save_state().to_bytes();
+    let response = bob
+        .server_receive(init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    let mut resumed = Uake::new();
+    resumed.restore_state(&UakeState::from_bytes(&saved).unwrap());
+    assert_eq!(resumed.state(), HandshakeState::AwaitingResponse);
+    resumed.client_confirm(response).unwrap();
+    assert_eq!(resumed.shared_secret, bob.shared_secret);
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let saved = alice.save_state().to_bytes();
+    let response = bob
+        .server_receive(
+            init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    let mut resumed = Ake::new();
+    resumed.restore_state(&AkeState::from_bytes(&saved).unwrap());
+    assert!(resumed.response_well_formed(&response, &bob_keys.public));
+    resumed
+        .client_confirm(response, &alice_keys.secret)
+        .unwrap();
+    assert_eq!(resumed.shared_secret, bob.shared_secret);
+    assert_eq!(
+        resumed.channel_binding().unwrap(),
+        bob.channel_binding().unwrap()
+    );
+
+    // Truncated bytes and unknown step tags are rejected
+    assert_eq!(
+        UakeState::from_bytes(&saved[..UAKE_STATE_BYTES - 1]),
+        Err(KyberLibError::InvalidLength)
+    );
+    let mut bad = saved;
+    bad[0] = 3;
+    assert_eq!(
+        AkeState::from_bytes(&bad),
+        Err(KyberLibError::InvalidInput)
+    );
+}
+
+#[test]
+fn split_init_matches_server_offsets() {
+    let mut rng = rand::thread_rng();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let init =
+        Uake::new().client_init(&bob_keys.public, &mut rng).unwrap();
+    let (pk, ct) = split_init(&init);
+    // uake_shared_b reads the ciphertext from recv[KYBER_PUBLIC_KEY_BYTES..]
+    assert_eq!(&pk[..], &init[..KYBER_PUBLIC_KEY_BYTES]);
+    assert_eq!(&ct[..], &init[KYBER_PUBLIC_KEY_BYTES..]);
+    assert!(decapsulate(ct, &bob_keys.secret).is_ok());
+    // The embedded public key accepts encapsulations
+    assert!(encapsulate(pk, &mut rng).is_ok());
+}
+
+#[test]
+fn server_receive_slice_checks_length() {
+    let mut rng = rand::thread_rng();
+    let alice_keys = keypair(&mut rng).unwrap();
+    let bob_keys = keypair(&mut rng).unwrap();
+
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    for len in [0, 1, UAKE_INIT_BYTES - 1, UAKE_INIT_BYTES + 1] {
+        let buf = vec![0u8; len];
+        assert_eq!(
+            bob.server_receive_slice(&buf, &bob_keys.secret, &mut rng),
+            Err(KyberLibError::InvalidLength)
+        );
+    }
+    let response = bob
+        .server_receive_slice(&init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    alice.client_confirm(response).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+
+    let mut alice = Ake::new();
+    let mut bob = Ake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(
+        bob.server_receive_slice(
+            &init[..AKE_INIT_BYTES - 1],
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        ),
+        Err(KyberLibError::InvalidLength)
+    );
+    let response = bob
+        .server_receive_slice(
+            &init,
+            &alice_keys.public,
+            &bob_keys.secret,
+            &mut rng,
+        )
+        .unwrap();
+    alice.client_confirm(response, &alice_keys.secret).unwrap();
+    assert_eq!(alice.shared_secret, bob.shared_secret);
+}
+
+#[test]
+fn uake_key_confirmation() {
+    let mut rng = rand::thread_rng();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let mut alice = Uake::new();
+    let mut bob = Uake::new();
+    let init = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    let response = bob
+        .server_receive(init, &bob_keys.secret, &mut rng)
+        .unwrap();
+    alice.client_confirm(response).unwrap();
+
+    let alice_tag = alice.key_confirmation().unwrap();
+    let bob_tag = bob.key_confirmation().unwrap();
+    assert!(alice.verify_key_confirmation(&bob_tag).unwrap());
+    assert!(bob.verify_key_confirmation(&alice_tag).unwrap());
+
+    // Each direction has its own tag, so reflecting one fails
+    assert_ne!(alice_tag, bob_tag);
+    assert!(!alice.verify_key_confirmation(&alice_tag).unwrap());
+    assert!(!bob.verify_key_confirmation(&bob_tag).unwrap());
+
+    // A party holding a different secret fails confirmation
+    bob.shared_secret[0] ^= 1;
+    let bob_tag = bob.key_confirmation().unwrap();
+    assert!(!alice.verify_key_confirmation(&bob_tag).unwrap());
+}
+
+#[test]
+fn uake_key_confirmation_before_confirmed() {
+    let mut rng = rand::thread_rng();
+    let bob_keys = keypair(&mut rng).unwrap();
+    let mut alice = Uake::new();
+    assert_eq!(
+        alice.key_confirmation(),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+    let _ = alice.client_init(&bob_keys.public, &mut rng).unwrap();
+    assert_eq!(
+        alice.verify_key_confirmation(&[0u8; 32]),
+        Err(KyberLibError::HandshakeOutOfOrder)
+    );
+}
@@ -56,6 +56,40 @@ mod tests {
             // Assert that the std feature is enabled
             assert!(cfg!(feature = "std"));
         }
+
+        #[test]
+        fn test_is_constant_time_stable() {
+            // Usable in const context and stable for this build
+            const CONSTANT_TIME: bool = kyberlib::is_constant_time();
+            assert_eq!(CONSTANT_TIME, kyberlib::is_constant_time());
+            // Native backends are constant-time, wasm32 makes no promise
+            assert_eq!(
+                kyberlib::is_constant_time(),
+                !cfg!(target_arch = "wasm32")
+            );
+        }
+
+        #[test]
+        fn test_build_info() {
+            use kyberlib::params::SecurityLevel;
+
+            let info = kyberlib::build_info();
+            let level = if cfg!(feature = "kyber512") {
+                SecurityLevel::Kyber512
+            } else if cfg!(feature = "kyber1024") {
+                SecurityLevel::Kyber1024
+            } else {
+                SecurityLevel::Kyber768
+            };
+            assert_eq!(info.level, level);
+            assert_eq!(info.mode_90s, cfg!(feature = "90s"));
+            assert_eq!(
+                info.avx2,
+                cfg!(all(target_arch = "x86_64", feature = "avx2"))
+            );
+            assert_eq!(info.zeroize, cfg!(feature = "zeroize"));
+        }
+
         #[test]
         #[should_panic(
             expected = "Only one security level can be specified"
@@ -5,10 +5,10 @@
 mod tests {
     use kyberlib::{
         keypair, kyberlib_ake_client_confirm, kyberlib_ake_client_init,
-        kyberlib_ake_server_receive, kyberlib_assert, kyberlib_max,
-        kyberlib_min, kyberlib_uake_client_confirm,
+        kyberlib_ake_server_receive, kyberlib_assert, kyberlib_ensure,
+        kyberlib_max, kyberlib_min, kyberlib_uake_client_confirm,
         kyberlib_uake_client_init, kyberlib_uake_server_receive, Ake,
-        Uake,
+        KyberLibError, Uake,
     };
     use kyberlib::{
         kyberlib_decrypt_message, kyberlib_encrypt_message,
@@ -17,6 +17,7 @@ mod tests {
     use kyberlib::{
         KYBER_CIPHERTEXT_BYTES, KYBER_PUBLIC_KEY_BYTES,
         KYBER_SECRET_KEY_BYTES, KYBER_SHARED_SECRET_BYTES,
+        KYBER_SYM_BYTES,
     };
     use rand_core::OsRng;
     use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
@@ -26,6 +27,16 @@ mod tests {
         kyberlib_assert!(1 + 1 == 2);
     }
 
+    #[test]
+    fn test_kyberlib_ensure() {
+        fn check(value: u8) -> Result<u8, KyberLibError> {
+            kyberlib_ensure!(value < 10, KyberLibError::InvalidInput);
+            Ok(value + 1)
+        }
+        assert_eq!(check(3), Ok(4));
+        assert_eq!(check(10), Err(KyberLibError::InvalidInput));
+    }
+
     #[test]
     fn test_kyberlib_min() {
         let min = kyberlib_min!(1, 2, 3);
@@ -116,6 +127,23 @@ mod tests {
         assert_eq!(secret_key.len(), KYBER_SECRET_KEY_BYTES);
     }
 
+    #[test]
+    fn test_generate_key_pair_macro_rejects_short_seed() {
+        let mut rng = OsRng;
+        let mut public_key = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        let mut secret_key = [0u8; KYBER_SECRET_KEY_BYTES];
+        let d = [1u8; KYBER_SYM_BYTES];
+        let z = [2u8; KYBER_SYM_BYTES - 1];
+
+        let result = kyberlib_generate_key_pair!(
+            &mut public_key,
+            &mut secret_key,
+            &mut rng,
+            Some((&d[..], &z[..]))
+        );
+        assert_eq!(result, Err(KyberLibError::InvalidInput));
+    }
+
     #[test]
     fn test_encrypt_message_macro() {
         let mut rng = OsRng;
@@ -219,10 +247,33 @@ mod tests {
     #[test]
     fn test_kyberlib_uake_client_confirm() {
         let mut rng = OsRng;
-        let alice = Uake::new();
-        let bob = Uake::new();
+        let mut alice = Uake::new();
+        let mut bob = Uake::new();
         let bob_keys = keypair(&mut rng).unwrap();
 
+        let client_init = kyberlib_uake_client_init!(
+            alice,
+            &bob_keys.public,
+            &mut rng
+        )
+        .unwrap();
+        let server_send = kyberlib_uake_server_receive!(
+            bob,
+            client_init,
+            &bob_keys.secret,
+            &mut rng
+        )
+        .unwrap();
+        kyberlib_uake_client_confirm!(alice, server_send).unwrap();
+
+        assert_eq!(alice.shared_secret, bob.shared_secret);
+    }
+
+    #[test]
+    fn test_kyberlib_uake_client_confirm_without_init() {
+        let mut rng = OsRng;
+        let mut alice = Uake::new();
+        let bob_keys = keypair(&mut rng).unwrap();
         let client_init =
             kyberlib_uake_client_init!(&bob_keys.public, &mut rng)
                 .unwrap();
@@ -232,9 +283,12 @@ mod tests {
             &mut rng
         )
         .unwrap();
-        kyberlib_uake_client_confirm!(server_send).unwrap();
 
-        assert_eq!(alice.shared_secret, bob.shared_secret);
+        // alice never sent client_init, so has no ephemeral keys
+        assert_eq!(
+            kyberlib_uake_client_confirm!(alice, server_send),
+            Err(KyberLibError::HandshakeOutOfOrder)
+        );
     }
 
     #[test]
@@ -270,23 +324,31 @@ mod tests {
     #[test]
     fn test_kyberlib_ake_client_confirm() {
         let mut rng = OsRng;
-        let alice = Ake::new();
-        let bob = Ake::new();
+        let mut alice = Ake::new();
+        let mut bob = Ake::new();
         let alice_keys = keypair(&mut rng).unwrap();
         let bob_keys = keypair(&mut rng).unwrap();
 
-        let client_init =
-            kyberlib_ake_client_init!(&bob_keys.public, &mut rng)
-                .unwrap();
+        let client_init = kyberlib_ake_client_init!(
+            alice,
+            &bob_keys.public,
+            &mut rng
+        )
+        .unwrap();
         let server_send = kyberlib_ake_server_receive!(
+            bob,
             client_init,
             &alice_keys.public,
             &bob_keys.secret,
             &mut rng
         )
         .unwrap();
-        kyberlib_ake_client_confirm!(server_send, &alice_keys.secret)
-            .unwrap();
+        kyberlib_ake_client_confirm!(
+            alice,
+            server_send,
+            &alice_keys.secret
+        )
+        .unwrap();
 
         assert_eq!(alice.shared_secret, bob.shared_secret);
     }
@@ -0,0 +1,56 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "metrics")]
+
+use kyberlib::{
+    decapsulate, decapsulate_with_context, encapsulate,
+    encapsulate_counter, encapsulate_with_context, keypair,
+    metrics::{clear_metrics_hook, set_metrics_hook, Operation},
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static KEYPAIRS: AtomicUsize = AtomicUsize::new(0);
+static ENCAPSULATIONS: AtomicUsize = AtomicUsize::new(0);
+static DECAPSULATIONS: AtomicUsize = AtomicUsize::new(0);
+
+fn record(op: Operation, _nanos: u64) {
+    let counter = match op {
+        Operation::Keypair => &KEYPAIRS,
+        Operation::Encapsulate => &ENCAPSULATIONS,
+        Operation::Decapsulate => &DECAPSULATIONS,
+    };
+    let _ = counter.fetch_add(1, Ordering::SeqCst);
+}
+
+// The installed hook observes keygen, encapsulation and decapsulation
+#[test]
+fn metrics_hook_observes_all_operations() {
+    let mut rng = rand::thread_rng();
+    set_metrics_hook(record);
+
+    let keys = keypair(&mut rng).unwrap();
+    let (ct, ss1) = encapsulate(&keys.public, &mut rng).unwrap();
+    let ss2 = decapsulate(&ct, &keys.secret).unwrap();
+    assert_eq!(ss1, ss2);
+
+    assert_eq!(KEYPAIRS.load(Ordering::SeqCst), 1);
+    assert_eq!(ENCAPSULATIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(DECAPSULATIONS.load(Ordering::SeqCst), 1);
+
+    // The other encapsulation and decapsulation entry points report too
+    let (ct, ss1) =
+        encapsulate_with_context(&keys.public, b"ctx", &mut rng)
+            .unwrap();
+    let ss2 =
+        decapsulate_with_context(&ct, &keys.secret, b"ctx").unwrap();
+    assert_eq!(ss1, ss2);
+    let _ = encapsulate_counter(&keys.public, &[7u8; 32], 0).unwrap();
+    assert_eq!(ENCAPSULATIONS.load(Ordering::SeqCst), 3);
+    assert_eq!(DECAPSULATIONS.load(Ordering::SeqCst), 2);
+
+    // Nothing is reported once the hook is removed
+    clear_metrics_hook();
+    let _keys = keypair(&mut rng).unwrap();
+    assert_eq!(KEYPAIRS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,149 @@
+// The NIST vectors only apply to the standard SHAKE instantiation
+#![cfg(not(feature = "turboshake"))]
+
+mod rsp;
+
+use core::convert::TryInto;
+use kyberlib::{
+    kem::{encrypt_message, generate_key_pair},
+    rng::NistDrbg,
+    *,
+};
+use rand_core::{CryptoRng, RngCore};
+use rsp::*;
+
+// Passed where an RNG is required although every coin comes from the
+// DRBG, so any draw from it means the seeded path reads fresh
+// randomness and the vectors cannot match
+#[derive(Debug)]
+struct UnusedRng;
+
+impl RngCore for UnusedRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, _dest: &mut [u8]) {
+        panic!("seeded KAT path drew from the RNG");
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for UnusedRng {}
+
+// Reproduce the NIST PQCkemKAT vectors for the compiled level,
+// drawing the keygen and encapsulation coins from the NIST DRBG
+// in the same order as the reference `PQCgenKAT_kem`.
+#[test]
+fn test_nist_kat_vectors() {
+    let kats = load_rsp();
+    assert!(kats.len() >= 10, "Expected at least 10 KAT vectors");
+    let mut rng = UnusedRng;
+    for kat in kats {
+        // Seed the DRBG and draw d, z and the encapsulation coins
+        let seed: [u8; 48] = kat.seed[..].try_into().unwrap();
+        let mut drbg = NistDrbg::new(&seed);
+        let mut d = [0u8; 32];
+        let mut z = [0u8; 32];
+        let mut coins = [0u8; 32];
+        drbg.fill_bytes(&mut d);
+        drbg.fill_bytes(&mut z);
+        drbg.fill_bytes(&mut coins);
+
+        // Key generation
+        let mut pk = [0u8; KYBER_PUBLIC_KEY_BYTES];
+        let mut sk = [0u8; KYBER_SECRET_KEY_BYTES];
+        generate_key_pair(&mut pk, &mut sk, &mut rng, Some((&d, &z)))
+            .unwrap();
+        assert_eq!(
+            &pk[..],
+            &kat.pk[..],
+            "pk mismatch, count {}",
+            kat.count
+        );
+        assert_eq!(
+            &sk[..],
+            &kat.sk[..],
+            "sk mismatch, count {}",
+            kat.count
+        );
+
+        // Encapsulation
+        let mut ct = [0u8; KYBER_CIPHERTEXT_BYTES];
+        let mut ss = [0u8; KYBER_SHARED_SECRET_BYTES];
+        encrypt_message(&mut ct, &mut ss, &pk, &mut rng, Some(&coins))
+            .unwrap();
+        assert_eq!(
+            &ct[..],
+            &kat.ct[..],
+            "ct mismatch, count {}",
+            kat.count
+        );
+        assert_eq!(
+            &ss[..],
+            &kat.ss[..],
+            "ss mismatch, count {}",
+            kat.count
+        );
+
+        // Decapsulation
+        let decapsulated = decapsulate(&kat.ct, &kat.sk).unwrap();
+        assert_eq!(
+            &decapsulated[..],
+            &kat.ss[..],
+            "Decapsulated ss mismatch, count {}",
+            kat.count
+        );
+    }
+}
+
+// keypair and encapsulate driven by the library's NistDrbg reproduce
+// the vectors through the public API alone
+#[test]
+fn test_nist_kat_public_api() {
+    for kat in load_rsp() {
+        let seed: [u8; 48] = kat.seed[..].try_into().unwrap();
+        let mut drbg = NistDrbg::new(&seed);
+        let keys = keypair(&mut drbg).unwrap();
+        assert_eq!(
+            &keys.public[..],
+            &kat.pk[..],
+            "count {}",
+            kat.count
+        );
+        assert_eq!(
+            &keys.secret[..],
+            &kat.sk[..],
+            "count {}",
+            kat.count
+        );
+        let (ct, ss) = encapsulate(&keys.public, &mut drbg).unwrap();
+        assert_eq!(&ct[..], &kat.ct[..], "count {}", kat.count);
+        assert_eq!(&ss[..], &kat.ss[..], "count {}", kat.count);
+    }
+}
+
+// The parser handles the header, blank lines and field order
+#[test]
+fn test_parse_rsp() {
+    let kats = parse_rsp(
+        "# kyber\n\ncount = 0\nseed = 00FF\npk = 01\nsk = 02\nct = 03\nss = 04\n\ncount = 1\nseed = AB\n",
+    );
+    assert_eq!(kats.len(), 2);
+    assert_eq!(kats[0].count, 0);
+    assert_eq!(kats[0].seed, vec![0x00, 0xff]);
+    assert_eq!(kats[0].ss, vec![0x04]);
+    assert_eq!(kats[1].count, 1);
+    assert_eq!(kats[1].seed, vec![0xab]);
+}
@@ -173,4 +173,96 @@ mod tests {
         // Test KYBER_CIPHERTEXT_BYTES
         assert_eq!(KYBER_CIPHERTEXT_BYTES, KYBER_INDCPA_BYTES);
     }
+
+    // Test sizes_for against the published Kyber sizes
+
+    #[test]
+    fn test_sizes_for() {
+        let expected = [
+            (SecurityLevel::Kyber512, 800, 1632, 768),
+            (SecurityLevel::Kyber768, 1184, 2400, 1088),
+            (SecurityLevel::Kyber1024, 1568, 3168, 1568),
+        ];
+        for (level, pk, sk, ct) in expected {
+            let sizes = sizes_for(level);
+            assert_eq!(sizes.public_key, pk);
+            assert_eq!(sizes.secret_key, sk);
+            assert_eq!(sizes.ciphertext, ct);
+            assert_eq!(sizes.shared_secret, 32);
+        }
+    }
+
+    #[test]
+    fn test_sizes_for_compiled_level() {
+        // The compiled level agrees with the compiled constants
+        let level = match KYBER_SECURITY_PARAMETER {
+            2 => SecurityLevel::Kyber512,
+            4 => SecurityLevel::Kyber1024,
+            _ => SecurityLevel::Kyber768,
+        };
+        let sizes = sizes_for(level);
+        assert_eq!(sizes.public_key, KYBER_PUBLIC_KEY_BYTES);
+        assert_eq!(sizes.secret_key, KYBER_SECRET_KEY_BYTES);
+        assert_eq!(sizes.ciphertext, KYBER_CIPHERTEXT_BYTES);
+        assert_eq!(sizes.shared_secret, KYBER_SHARED_SECRET_BYTES);
+    }
+
+    #[test]
+    fn test_len_for() {
+        let expected = [
+            (SecurityLevel::Kyber512, 800, 768),
+            (SecurityLevel::Kyber768, 1184, 1088),
+            (SecurityLevel::Kyber1024, 1568, 1568),
+        ];
+        for (level, pk, ct) in expected {
+            assert_eq!(public_key_len_for(level), pk);
+            assert_eq!(ciphertext_len_for(level), ct);
+            assert_eq!(shared_secret_len_for(level), 32);
+        }
+    }
+
+    #[test]
+    fn test_from_public_key_len() {
+        for level in [
+            SecurityLevel::Kyber512,
+            SecurityLevel::Kyber768,
+            SecurityLevel::Kyber1024,
+        ] {
+            let len = sizes_for(level).public_key;
+            assert_eq!(
+                SecurityLevel::from_public_key_len(len),
+                Some(level)
+            );
+        }
+        for len in [0, 799, 1088, 1569, KYBER_SECRET_KEY_BYTES] {
+            assert_eq!(SecurityLevel::from_public_key_len(len), None);
+        }
+    }
+
+    #[test]
+    fn test_stack_estimates() {
+        let keygen = keygen_stack_estimate();
+        let encap = encapsulate_stack_estimate();
+        let decap = decapsulate_stack_estimate();
+        assert!(keygen > 0 && encap > 0 && decap > 0);
+        // Decapsulation re-encrypts, so it needs at least as much
+        assert!(decap > encap);
+
+        // The matrix alone grows with k^2, so each level has a floor
+        let matrix = KYBER_SECURITY_PARAMETER
+            * KYBER_SECURITY_PARAMETER
+            * KYBER_N
+            * 2;
+        assert!(keygen > matrix && encap > matrix);
+        let (low, high) = if cfg!(feature = "kyber512") {
+            (8_000, 16_000)
+        } else if cfg!(feature = "kyber1024") {
+            (20_000, 40_000)
+        } else {
+            (12_000, 28_000)
+        };
+        for estimate in [keygen, encap, decap] {
+            assert!(estimate > low && estimate < high);
+        }
+    }
 }
@@ -1,9 +1,12 @@
 // Copyright © 2024 kyberlib. All rights reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+mod utils;
+
 #[cfg(test)]
 mod tests {
 
+    use super::utils::FailingRng;
     use kyberlib::{rng::randombytes, KyberLibError};
     use rand_core::OsRng;
 
@@ -203,4 +206,46 @@ mod tests {
         // Check if the result is an InvalidLength error
         assert!(matches!(result, Err(KyberLibError::InvalidLength)));
     }
+
+    #[test]
+    fn test_randombytes_failure_reports_length() {
+        // A failing RNG reports how many bytes were requested
+        let mut buffer = [0u8; 48];
+        let result =
+            randombytes(&mut buffer, 40, &mut FailingRng::default());
+        assert_eq!(
+            result,
+            Err(KyberLibError::RandomBytesGenerationSized {
+                requested: 40
+            })
+        );
+    }
+
+    // Seeded with entropy 0..48, as in PQCgenKAT_kem, the DRBG yields
+    // the published per-vector seeds of the NIST .rsp files
+    #[cfg(feature = "nist-drbg")]
+    #[test]
+    fn test_nist_drbg_kat_seeds() {
+        use kyberlib::rng::NistDrbg;
+        use rand_core::RngCore;
+
+        let mut entropy = [0u8; 48];
+        for (i, byte) in entropy.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut drbg = NistDrbg::new(&entropy);
+        let mut seed = [0u8; 48];
+        drbg.fill_bytes(&mut seed);
+        assert_eq!(
+            seed[..16],
+            [
+                0x06, 0x15, 0x50, 0x23, 0x4d, 0x15, 0x8c, 0x5e, 0xc9,
+                0x55, 0x95, 0xfe, 0x04, 0xef, 0x7a, 0x25
+            ]
+        );
+        assert_eq!(seed[47], 0xa1);
+        drbg.fill_bytes(&mut seed);
+        assert_eq!(seed[..4], [0xd8, 0x1c, 0x4d, 0x8d]);
+        assert_eq!(seed[44..], [0xad, 0xfa, 0x95, 0x8f]);
+    }
 }
@@ -0,0 +1,68 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "fips202-export")]
+
+#[cfg(test)]
+mod tests {
+    use kyberlib::sha3::{sha3_256, sha3_512, shake128, shake256};
+
+    // Decodes hex string into a vector of bytes
+    fn decode_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // SHA3-256("abc") from the FIPS 202 examples
+    #[test]
+    fn test_sha3_256_known_vector() {
+        let expected = decode_hex(
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        );
+        assert_eq!(sha3_256(b"abc")[..], expected[..]);
+    }
+
+    // SHA3-512("abc") from the FIPS 202 examples
+    #[test]
+    fn test_sha3_512_known_vector() {
+        let expected = decode_hex(
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d02\
+             40d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a5\
+             6592f8274eec53f0",
+        );
+        assert_eq!(sha3_512(b"abc")[..], expected[..]);
+    }
+
+    // SHAKE128 of the empty string, 32 bytes of output
+    #[test]
+    fn test_shake128_known_vector() {
+        let expected = decode_hex(
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+        );
+        let mut out = [0u8; 32];
+        shake128(&mut out, b"");
+        assert_eq!(out[..], expected[..]);
+    }
+
+    // SHAKE256 of the empty string, 64 bytes of output
+    #[test]
+    fn test_shake256_known_vector() {
+        let expected = decode_hex(
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+             d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+        );
+        let mut out = [0u8; 64];
+        shake256(&mut out, b"");
+        assert_eq!(out[..], expected[..]);
+    }
+
+    // Output longer than one rate block is squeezed correctly
+    #[test]
+    fn test_shake128_multiple_blocks() {
+        let mut out = [0u8; 200];
+        shake128(&mut out, b"abc");
+        assert_eq!(out[192..], decode_hex("4818cb006aa5b4cd")[..]);
+    }
+}
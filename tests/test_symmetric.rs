@@ -5,8 +5,11 @@
 mod tests {
     // Import necessary items
     use kyberlib::{
-        symmetric::{hash_g, hash_h, kdf, prf},
-        KYBER_SHARED_SECRET_BYTES,
+        symmetric::{
+            aes_backend, hash_g, hash_h, kdf, prf, symmetric_backend,
+            xof_block_bytes,
+        },
+        KyberLibError, KYBER_90S, KYBER_SHARED_SECRET_BYTES,
     };
 
     // Test the hash_h function
@@ -17,7 +20,7 @@ mod tests {
         let mut out = [0u8; 32];
 
         // Call the hash_h function
-        hash_h(&mut out, input, inlen);
+        hash_h(&mut out, input, inlen).unwrap();
 
         // Assert that the output is not all zeros
         assert_ne!(out, [0u8; 32]);
@@ -31,13 +34,48 @@ mod tests {
         let mut out = [0u8; 64];
 
         // Call the hash_g function
-        hash_g(&mut out, input, inlen);
+        hash_g(&mut out, input, inlen).unwrap();
 
         // Assert that the output is not all zeros
         assert_ne!(out, [0u8; 64]);
     }
 
-    // Test the xof_absorb and xof_squeezeblocks functions
+    // An inlen past the end of the input errors instead of panicking
+    #[test]
+    fn test_hash_h_inlen_too_long() {
+        let input = b"test input";
+        let mut out = [0u8; 32];
+        assert_eq!(
+            hash_h(&mut out, input, input.len() + 1),
+            Err(KyberLibError::InvalidLength)
+        );
+        assert_eq!(
+            hash_h(&mut out[..31], input, input.len()),
+            Err(KyberLibError::InvalidLength)
+        );
+        assert_eq!(out, [0u8; 32]);
+    }
+
+    // An inlen past the end of the input errors instead of panicking
+    #[test]
+    fn test_hash_g_inlen_too_long() {
+        let input = b"test input";
+        let mut out = [0u8; 64];
+        assert_eq!(
+            hash_g(&mut out, input, usize::MAX),
+            Err(KyberLibError::InvalidLength)
+        );
+        assert_eq!(
+            hash_g(&mut out[..63], input, input.len()),
+            Err(KyberLibError::InvalidLength)
+        );
+        assert_eq!(out, [0u8; 64]);
+    }
+
+    // Test the xof_absorb and xof_squeezeblocks functions. The fixed
+    // input happens to squeeze no zero bytes under SHAKE128; TurboSHAKE
+    // produces a different stream, so this check is SHAKE-specific.
+    #[cfg(not(feature = "turboshake"))]
     #[test]
     fn test_xof_absorb_squeeze() {
         use kyberlib::params::KYBER_SYM_BYTES;
@@ -143,4 +181,40 @@ mod tests {
         // Assert that the output is not all zeros
         assert_ne!(out, [0u8; 32]);
     }
+
+    // Test that xof_block_bytes matches the active mode's block size
+    #[test]
+    fn test_xof_block_bytes() {
+        let expected = if KYBER_90S { 64 } else { 168 };
+        assert_eq!(xof_block_bytes(), expected);
+        assert_eq!(
+            xof_block_bytes(),
+            kyberlib::symmetric::XOF_BLOCKBYTES
+        );
+    }
+
+    // Test the symmetric_backend function
+    #[test]
+    fn test_symmetric_backend() {
+        // The backend name follows the 90s and turboshake features
+        let expected = if KYBER_90S {
+            "aes-sha2"
+        } else if cfg!(feature = "turboshake") {
+            "turboshake"
+        } else {
+            "shake"
+        };
+        assert_eq!(symmetric_backend(), expected);
+    }
+
+    // aes_backend reports the AES implementation the features select
+    #[test]
+    fn test_aes_backend_matches_features() {
+        #[cfg(not(feature = "90s"))]
+        assert_eq!(aes_backend(), "none");
+        #[cfg(all(feature = "90s", not(feature = "90s-fixslice")))]
+        assert_eq!(aes_backend(), "bitslice");
+        #[cfg(feature = "90s-fixslice")]
+        assert_eq!(aes_backend(), "fixslice");
+    }
 }
@@ -0,0 +1,45 @@
+// Copyright © 2024 kyberlib. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "testing")]
+
+use kyberlib::{testing::*, *};
+
+// keypair reports the failing draw and succeeds with enough budget
+#[test]
+fn test_keypair_with_testing_rngs() {
+    assert_eq!(
+        keypair(&mut FailingRng),
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
+    );
+    let mut rng = CountingRng::new(1 << 20);
+    assert!(keypair(&mut rng).is_ok());
+    assert_eq!(rng.used(), 2 * KYBER_SYM_BYTES);
+}
+
+// CountingRng fails the draw that would exceed its limit
+#[test]
+fn test_counting_rng_limit() {
+    let keys = keypair(&mut CountingRng::new(64)).unwrap();
+    let mut rng = CountingRng::new(KYBER_SYM_BYTES - 1);
+    assert_eq!(
+        encapsulate(&keys.public, &mut rng),
+        Err(KyberLibError::RandomBytesGenerationSized {
+            requested: KYBER_SYM_BYTES
+        })
+    );
+    assert_eq!(rng.used(), 0);
+    assert!(
+        encapsulate(&keys.public, &mut CountingRng::new(32)).is_ok()
+    );
+}
+
+// The same label reproduces a keypair and different labels do not
+#[test]
+fn test_labeled_rng() {
+    let a = keypair(&mut labeled_rng("alice")).unwrap();
+    assert_eq!(a, keypair(&mut labeled_rng("alice")).unwrap());
+    assert_ne!(a, keypair(&mut labeled_rng("bob")).unwrap());
+}
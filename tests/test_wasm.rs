@@ -4,7 +4,7 @@
 #[cfg(test)]
 mod tests {
     // Import necessary items
-    use kyberlib::wasm::{Kex, Keys, Params};
+    use kyberlib::wasm::{kem_demo, Kex, Keys, Params};
     use kyberlib::{decapsulate, encapsulate, keypair, params::*};
     use wasm_bindgen_test::*;
 
@@ -103,12 +103,34 @@ mod tests {
             vec![0u8; KYBER_CIPHERTEXT_BYTES].into_boxed_slice();
         let new_ss =
             vec![0u8; KYBER_SHARED_SECRET_BYTES].into_boxed_slice();
-        kex.set_ciphertext(new_ct.clone());
-        kex.set_sharedSecret(new_ss.clone());
+        assert!(kex.set_ciphertext(new_ct.clone()).is_ok());
+        assert!(kex.set_sharedSecret(new_ss.clone()).is_ok());
         assert_eq!(kex.ciphertext(), new_ct);
         assert_eq!(kex.sharedSecret(), new_ss);
     }
 
+    // Test that the Kex setters reject wrong-length values
+    #[wasm_bindgen_test]
+    fn test_kex_setters_reject_wrong_length() {
+        let keys = match Keys::new() {
+            Ok(keys) => keys,
+            Err(_) => return,
+        };
+        let mut kex = Kex::new(keys.pubkey());
+        let ct = kex.ciphertext();
+
+        let short_ct =
+            vec![0u8; KYBER_CIPHERTEXT_BYTES - 1].into_boxed_slice();
+        assert!(kex.set_ciphertext(short_ct).is_err());
+        let long_ss =
+            vec![0u8; KYBER_SHARED_SECRET_BYTES + 1].into_boxed_slice();
+        assert!(kex.set_sharedSecret(long_ss).is_err());
+
+        // The rejected values are not stored
+        assert_eq!(kex.ciphertext(), ct);
+        assert_eq!(kex.sharedSecret().len(), KYBER_SHARED_SECRET_BYTES);
+    }
+
     // Test the Kex new method
     #[wasm_bindgen_test]
     fn test_kex_new() {
@@ -126,6 +148,55 @@ mod tests {
         assert_eq!(keys.secret().len(), KYBER_SECRET_KEY_BYTES);
     }
 
+    // Test that free_secret zeroes the secret key
+    #[wasm_bindgen_test]
+    fn test_keys_free_secret() {
+        let mut keys = match Keys::new() {
+            Ok(keys) => keys,
+            Err(_) => return,
+        };
+        keys.free_secret();
+        assert_eq!(keys.secret().len(), KYBER_SECRET_KEY_BYTES);
+        assert!(keys.secret().iter().all(|&b| b == 0));
+        assert_eq!(keys.pubkey().len(), KYBER_PUBLIC_KEY_BYTES);
+    }
+
+    // Test that to_keypair gives a working core Keypair
+    #[wasm_bindgen_test]
+    fn test_keys_to_keypair() {
+        let keys = match Keys::new() {
+            Ok(keys) => keys,
+            Err(_) => return,
+        };
+        let keypair = keys.to_keypair().unwrap();
+        assert_eq!(&keypair.public[..], &keys.pubkey()[..]);
+        let mut rng = rand::rngs::OsRng {};
+        let (ct, ss) = encapsulate(&keypair.public, &mut rng).unwrap();
+        assert_eq!(decapsulate(&ct, &keypair.secret).unwrap(), ss);
+    }
+
+    // Test that kem_demo returns a matching key pair and shared secret
+    #[wasm_bindgen_test]
+    fn test_kem_demo() {
+        let demo = match kem_demo() {
+            Ok(demo) => demo,
+            Err(_) => return,
+        };
+        assert_eq!(demo.pubkey().len(), KYBER_PUBLIC_KEY_BYTES);
+        assert_eq!(demo.ciphertext().len(), KYBER_CIPHERTEXT_BYTES);
+
+        // The shared secret decapsulates with the matching secret key
+        let ss =
+            decapsulate(&demo.ciphertext(), &demo.secret()).unwrap();
+        assert_eq!(&ss[..], &demo.sharedSecret()[..]);
+    }
+
+    // WebAssembly builds do not claim constant-time execution
+    #[wasm_bindgen_test]
+    fn test_is_constant_time_on_wasm() {
+        assert!(!kyberlib::is_constant_time());
+    }
+
     // Test the Params struct
     #[wasm_bindgen_test]
     fn test_params() {
@@ -274,8 +345,8 @@ mod tests {
             vec![2u8; KYBER_SHARED_SECRET_BYTES].into_boxed_slice();
 
         // Set the new ciphertext and shared secret
-        kex.set_ciphertext(new_ct.clone());
-        kex.set_sharedSecret(new_ss.clone());
+        assert!(kex.set_ciphertext(new_ct.clone()).is_ok());
+        assert!(kex.set_sharedSecret(new_ss.clone()).is_ok());
 
         // Check if the ciphertext and shared secret are updated correctly
         assert_eq!(kex.ciphertext(), new_ct);